@@ -1,9 +1,12 @@
-use bracket_lib::prelude::{FontCharType, Point, RGB};
+use bracket_lib::prelude::{FontCharType, Point, DODGERBLUE, GOLD, RGB};
 use serde::{Deserialize, Serialize};
 use specs::error::NoError;
 use specs::prelude::*;
 use specs::saveload::{ConvertSaveload, Marker};
 use specs_derive::*;
+use std::collections::HashMap;
+
+use super::gamesystem::attr_bonus;
 
 #[derive(Component, ConvertSaveload, Clone)]
 pub struct Position {
@@ -44,8 +47,59 @@ pub struct BlocksTile;
 pub struct CombatStats {
     pub max_hp: i32,
     pub hp: i32,
-    pub defense: i32,
-    pub power: i32,
+    pub level: i32,
+    pub xp: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Attribute {
+    pub base: i32,
+    pub modifiers: i32,
+    pub bonus: i32,
+}
+
+impl Attribute {
+    pub fn new(base: i32) -> Self {
+        Attribute {
+            base,
+            modifiers: 0,
+            bonus: attr_bonus(base),
+        }
+    }
+
+    pub fn value(&self) -> i32 {
+        self.base + self.modifiers
+    }
+}
+
+#[derive(Component, Serialize, Deserialize, Debug, Clone)]
+pub struct Attributes {
+    pub might: Attribute,
+    pub fitness: Attribute,
+    pub quickness: Attribute,
+    pub intelligence: Attribute,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Skill {
+    Melee,
+    Ranged,
+    Defense,
+    Magic,
+}
+
+#[derive(Component, Serialize, Deserialize, Debug, Clone)]
+pub struct Skills {
+    pub skills: HashMap<Skill, i32>,
+}
+
+/// Worn equipment carrying this adds to (or takes from) its owner's attributes.
+#[derive(Component, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AttributeBonus {
+    pub might: i32,
+    pub fitness: i32,
+    pub quickness: i32,
+    pub intelligence: i32,
 }
 
 #[derive(Component, Debug, ConvertSaveload, Clone)]
@@ -53,26 +107,53 @@ pub struct WantsToMelee {
     pub target: Entity,
 }
 
-#[derive(Component, Debug, ConvertSaveload, Clone)]
+/// Damage waiting to be applied, along with whoever dealt it and whether it
+/// came from a weapon (which armor can soak). It never outlives a tick, so it
+/// isn't saved.
+#[derive(Component, Debug, Clone)]
 pub struct SufferDamage {
-    pub amount: Vec<i32>,
+    pub amount: Vec<(i32, DamageType, Option<Entity>, bool)>,
 }
 
 impl SufferDamage {
-    pub fn new_damage(store: &mut WriteStorage<SufferDamage>, victim: Entity, amount: i32) {
+    pub fn new_damage(
+        store: &mut WriteStorage<SufferDamage>,
+        victim: Entity,
+        amount: i32,
+        damage_type: DamageType,
+        from: Option<Entity>,
+        weapon: bool,
+    ) {
         if let Some(suffering) = store.get_mut(victim) {
-            suffering.amount.push(amount);
+            suffering.amount.push((amount, damage_type, from, weapon));
         } else {
             let dmg = SufferDamage {
-                amount: vec![amount],
+                amount: vec![(amount, damage_type, from, weapon)],
             };
             store.insert(victim, dmg).expect("Unable to insert damage!");
         }
     }
 }
 
+/// Anything that can be carried; weight is in pounds, value in gold.
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
-pub struct Item;
+pub struct Item {
+    pub weight: f32,
+    pub value: i32,
+}
+
+/// What an entity is hauling around, recomputed every turn.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Encumbrance {
+    pub weight: f32,
+    pub capacity: f32,
+}
+
+impl Encumbrance {
+    pub fn overloaded(&self) -> bool {
+        self.weight > self.capacity
+    }
+}
 
 #[derive(Component, Debug, ConvertSaveload, Clone)]
 pub struct ProvidesHealing {
@@ -104,14 +185,57 @@ pub struct WantsToDropItem {
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct Consumable;
 
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Potion {}
+
+/// A weapon balanced for throwing; it hurts whatever it hits.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Throwable {}
+
 #[derive(Component, Debug, ConvertSaveload, Clone)]
 pub struct Ranged {
     pub range: i32,
 }
 
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum DamageType {
+    Physical,
+    Fire,
+    Cold,
+    Poison,
+    Lightning,
+}
+
+impl DamageType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DamageType::Physical => "blow",
+            DamageType::Fire => "fire",
+            DamageType::Cold => "cold",
+            DamageType::Poison => "poison",
+            DamageType::Lightning => "lightning",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum Resistance {
+    Resistant,
+    Immune,
+    Vulnerable,
+}
+
+/// On a creature this describes its own hide; on worn equipment it protects
+/// the wearer.
+#[derive(Component, Serialize, Deserialize, Debug, Clone)]
+pub struct Resistances {
+    pub resistances: HashMap<DamageType, Resistance>,
+}
+
 #[derive(Component, Debug, ConvertSaveload, Clone)]
 pub struct InflictsDamage {
     pub damage: i32,
+    pub damage_type: DamageType,
 }
 
 #[derive(Component, Debug, ConvertSaveload, Clone)]
@@ -119,9 +243,101 @@ pub struct AreaOfEffect {
     pub radius: i32,
 }
 
-#[derive(Component, Debug, ConvertSaveload, Clone)]
-pub struct Confusion {
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum StatusKind {
+    Poisoned,
+    Regenerating,
+    Hasted,
+    Slowed,
+    Paralyzed,
+    Blinded,
+    Confused,
+}
+
+impl StatusKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusKind::Poisoned => "poisoned",
+            StatusKind::Regenerating => "regenerating",
+            StatusKind::Hasted => "hasted",
+            StatusKind::Slowed => "slowed",
+            StatusKind::Paralyzed => "paralyzed",
+            StatusKind::Blinded => "blinded",
+            StatusKind::Confused => "confused",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub turns: i32,
+    pub potency: i32,
+}
+
+/// Every status effect currently affecting an entity. `StatusSystem` ticks
+/// them down and removes the component once the last one wears off.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct StatusEffects {
+    pub effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    /// Poison stacks its potency; anything else already present only has its
+    /// duration extended.
+    pub fn apply(store: &mut WriteStorage<StatusEffects>, target: Entity, effect: StatusEffect) {
+        if let Some(statuses) = store.get_mut(target) {
+            if let Some(existing) = statuses.effects.iter_mut().find(|e| e.kind == effect.kind) {
+                existing.turns = i32::max(existing.turns, effect.turns);
+                if effect.kind == StatusKind::Poisoned {
+                    existing.potency += effect.potency;
+                } else {
+                    existing.potency = i32::max(existing.potency, effect.potency);
+                }
+            } else {
+                statuses.effects.push(effect);
+            }
+        } else {
+            store
+                .insert(
+                    target,
+                    StatusEffects {
+                        effects: vec![effect],
+                    },
+                )
+                .expect("Unable to insert status.");
+        }
+    }
+
+    pub fn get(&self, kind: StatusKind) -> Option<&StatusEffect> {
+        self.effects.iter().find(|e| e.kind == kind)
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.get(kind).is_some()
+    }
+
+    /// Paralysis stops every action, and slowness every other one.
+    pub fn loses_turn(&self) -> bool {
+        self.has(StatusKind::Paralyzed)
+            || self
+                .get(StatusKind::Slowed)
+                .is_some_and(|slow| slow.turns % 2 == 0)
+    }
+
+    /// Haste grants an extra action every other turn.
+    pub fn extra_turn(&self) -> bool {
+        self.get(StatusKind::Hasted)
+            .is_some_and(|haste| haste.turns % 2 == 0)
+    }
+}
+
+/// Items carrying this put a status effect on whoever they're used on.
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
+pub struct InflictsStatus {
+    pub kind: StatusKind,
     pub turns: i32,
+    pub potency: i32,
 }
 
 pub struct SerializeMe;
@@ -129,12 +345,40 @@ pub struct SerializeMe;
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct SerializationHelper {
     pub map: super::map::Map,
+    pub identification: super::Identification,
 }
 
 #[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum EquipmentSlot {
     Melee,
     Shield,
+    Ranged,
+    Head,
+    Torso,
+    Legs,
+    Feet,
+    Hands,
+    Amulet,
+    Ring1,
+    Ring2,
+}
+
+impl EquipmentSlot {
+    pub fn name(&self) -> &str {
+        match self {
+            EquipmentSlot::Melee => "main hand",
+            EquipmentSlot::Shield => "off hand",
+            EquipmentSlot::Ranged => "ranged",
+            EquipmentSlot::Head => "head",
+            EquipmentSlot::Torso => "torso",
+            EquipmentSlot::Legs => "legs",
+            EquipmentSlot::Feet => "feet",
+            EquipmentSlot::Hands => "hands",
+            EquipmentSlot::Amulet => "amulet",
+            EquipmentSlot::Ring1 => "left ring",
+            EquipmentSlot::Ring2 => "right ring",
+        }
+    }
 }
 
 #[derive(Component, Serialize, Deserialize, Clone)]
@@ -142,6 +386,84 @@ pub struct Equippable {
     pub slot: EquipmentSlot,
 }
 
+/// Equipment that won't come off once worn. Nobody knows until they try.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Cursed {
+    pub known: bool,
+}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct RemovesCurse {}
+
+/// A pool of mana that spells are cast from; it trickles back over time.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Mana {
+    pub mana: i32,
+    pub max_mana: i32,
+    pub regen_timer: i32,
+}
+
+/// A spell `owner` has learned. Spells carry the same effect components as
+/// the items they imitate.
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct KnownSpell {
+    pub owner: Entity,
+    pub mana_cost: i32,
+}
+
+/// Reading this teaches the named spell.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Spellbook {
+    pub spell: String,
+}
+
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct WantsToCastSpell {
+    pub spell: Entity,
+    pub target: Option<Point>,
+}
+
+/// Several identical consumables carried as a single backpack entry.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Stack {
+    pub count: i32,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Rarity {
+    Magic,
+    Rare,
+}
+
+impl Rarity {
+    pub fn color(&self) -> RGB {
+        match self {
+            Rarity::Magic => RGB::named(DODGERBLUE),
+            Rarity::Rare => RGB::named(GOLD),
+        }
+    }
+}
+
+/// Equipment that rolled one or more affixes when it was generated.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct MagicItem {
+    pub rarity: Rarity,
+}
+
+/// A wand or staff that works a limited number of times, and is kept once empty.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Charges {
+    pub uses: i32,
+    pub max_uses: i32,
+}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Recharges {}
+
+/// A weapon that needs both hands, leaving no room for a shield.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct TwoHanded {}
+
 #[derive(Component, ConvertSaveload, Clone)]
 pub struct Equipped {
     pub owner: Entity,
@@ -153,11 +475,69 @@ pub struct MeleePowerBonus {
     pub power: i32,
 }
 
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct MeleeWeapon {
+    pub hit_bonus: i32,
+    pub damage_n_dice: i32,
+    pub damage_die_type: i32,
+    pub damage_bonus: i32,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum AmmoType {
+    Arrow,
+    Bolt,
+}
+
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct RangedWeapon {
+    pub range: i32,
+    pub hit_bonus: i32,
+    pub damage_n_dice: i32,
+    pub damage_die_type: i32,
+    pub damage_bonus: i32,
+    pub ammo: AmmoType,
+}
+
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Ammunition {
+    pub ammo_type: AmmoType,
+}
+
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct WantsToShoot {
+    pub ammo: Entity,
+    pub target: Point,
+}
+
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct WantsToThrow {
+    pub item: Entity,
+    pub target: Point,
+}
+
+/// Evasion: makes the wearer harder to hit in the first place.
 #[derive(Component, ConvertSaveload, Clone)]
 pub struct DefenseBonus {
     pub defense: i32,
 }
 
+/// Soaks up part of every physical blow that does land.
+#[derive(Component, ConvertSaveload, Clone)]
+pub struct DamageReduction {
+    pub amount: i32,
+}
+
+/// Armor that clanks with every step, waking anything nearby.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct HeavyArmor {}
+
+/// Percent chance to stop an attack outright.
+#[derive(Component, ConvertSaveload, Clone)]
+pub struct BlockChance {
+    pub percent: i32,
+}
+
 #[derive(Component, Debug, ConvertSaveload, Clone)]
 pub struct WantsToRemoveItem {
     pub item: Entity,
@@ -170,20 +550,30 @@ pub struct ParticleLifetime {
 
 #[derive(Serialize, Deserialize, Copy, Clone, PartialEq)]
 pub enum HungerState {
+    Gorged,
     WellFed,
     Normal,
     Hungry,
     Starving,
 }
 
+/// Satiation drops by one every turn; the hunger state follows from it.
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct HungerClock {
     pub state: HungerState,
-    pub duration: i32,
+    pub satiation: i32,
+}
+
+impl HungerClock {
+    pub fn hungry(&self) -> bool {
+        self.state == HungerState::Hungry || self.state == HungerState::Starving
+    }
 }
 
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
-pub struct ProvidesFood;
+pub struct ProvidesFood {
+    pub nutrition: i32,
+}
 
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct MagicMapper;
@@ -199,3 +589,57 @@ pub struct EntityMoved;
 
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct SingleActivation;
+
+/// A trap that flings its victim somewhere else on the level.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Teleports;
+
+/// A trap that wakes up the whole level.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Alarm;
+
+/// A trap that drops the player down to the next depth.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Trapdoor;
+
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq)]
+pub enum AlertState {
+    Asleep,
+    Unaware,
+}
+
+/// Monsters without this component are fully alert.
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Alertness {
+    pub state: AlertState,
+}
+
+/// Monsters sharing a `pack` id were spawned together and hunt as a group.
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct PackMember {
+    pub pack: usize,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq)]
+pub enum AllyOrder {
+    Follow,
+    Stay,
+    Attack,
+}
+
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Ally {
+    pub order: AllyOrder,
+}
+
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct AllyTarget {
+    pub target: Entity,
+}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct SummonsAlly;
+
+/// Follows the player's scent trail when it loses sight of them.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Tracker;
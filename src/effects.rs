@@ -0,0 +1,216 @@
+use crate::{DamageType, Map, Position, StatusKind};
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+use std::collections::VecDeque;
+
+mod charges;
+mod curse;
+mod damage;
+mod food;
+mod particles;
+mod spells;
+mod status;
+mod summon;
+mod traps;
+mod triggers;
+
+pub enum EffectType {
+    Damage {
+        amount: i32,
+        damage_type: DamageType,
+        /// Struck by a melee, ranged or thrown weapon, so armor soaks it
+        weapon: bool,
+    },
+    Healing {
+        amount: i32,
+    },
+    Status {
+        kind: StatusKind,
+        turns: i32,
+        potency: i32,
+    },
+    Feed {
+        nutrition: i32,
+    },
+    MagicMapping,
+    SummonAlly,
+    RemoveCurse,
+    Recharge,
+    LearnSpell {
+        spell: String,
+    },
+    Teleport,
+    Alarm,
+    Trapdoor,
+    Particle {
+        glyph: FontCharType,
+        fg: RGB,
+        bg: RGB,
+        lifetime: f32,
+    },
+    ItemUse {
+        item: Entity,
+    },
+    TriggerFire {
+        trigger: Entity,
+    },
+}
+
+#[derive(Clone)]
+pub enum Targets {
+    Single { target: Entity },
+    Tile { tile_idx: i32 },
+    Tiles { tiles: Vec<i32> },
+    Area { center: i32, radius: i32 },
+}
+
+pub struct EffectSpawner {
+    pub creator: Option<Entity>,
+    pub effect_type: EffectType,
+    pub targets: Targets,
+}
+
+/// Everything that wants to change the world — items, traps, combat — queues
+/// an effect here, and `run_effects_queue` applies them all in one place.
+pub struct EffectQueue {
+    queue: VecDeque<EffectSpawner>,
+}
+
+impl Default for EffectQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EffectQueue {
+    pub fn new() -> Self {
+        EffectQueue {
+            queue: VecDeque::new(),
+        }
+    }
+
+    pub fn add_effect(
+        &mut self,
+        creator: Option<Entity>,
+        effect_type: EffectType,
+        targets: Targets,
+    ) {
+        self.queue.push_back(EffectSpawner {
+            creator,
+            effect_type,
+            targets,
+        });
+    }
+}
+
+pub fn run_effects_queue(ecs: &mut World) {
+    loop {
+        let effect = ecs.write_resource::<EffectQueue>().queue.pop_front();
+        match effect {
+            Some(effect) => target_applicator(ecs, &effect),
+            None => break,
+        }
+    }
+}
+
+fn target_applicator(ecs: &mut World, effect: &EffectSpawner) {
+    match effect.effect_type {
+        EffectType::ItemUse { item } => {
+            triggers::item_trigger(effect.creator, item, &effect.targets, ecs)
+        }
+        EffectType::TriggerFire { trigger } => {
+            triggers::trap_trigger(trigger, &effect.targets, ecs)
+        }
+        _ => match &effect.targets {
+            Targets::Single { target } => affect_entity(ecs, effect, *target),
+            Targets::Tile { tile_idx } => affect_tile(ecs, effect, *tile_idx),
+            Targets::Tiles { tiles } => {
+                for tile_idx in tiles.iter() {
+                    affect_tile(ecs, effect, *tile_idx);
+                }
+            }
+            Targets::Area { .. } => {
+                for tile_idx in target_tiles(ecs, &effect.targets) {
+                    affect_tile(ecs, effect, tile_idx);
+                }
+            }
+        },
+    }
+}
+
+fn affect_tile(ecs: &mut World, effect: &EffectSpawner, tile_idx: i32) {
+    if let EffectType::Particle { .. } = effect.effect_type {
+        particles::particle_to_tile(ecs, tile_idx, effect);
+        return;
+    }
+
+    let content = ecs.fetch::<Map>().tile_content[tile_idx as usize].clone();
+    for entity in content.iter() {
+        affect_entity(ecs, effect, *entity);
+    }
+}
+
+fn affect_entity(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
+    match &effect.effect_type {
+        EffectType::Damage { .. } => damage::inflict_damage(ecs, effect, target),
+        EffectType::Healing { .. } => damage::heal_damage(ecs, effect, target),
+        EffectType::Status { .. } => status::apply_status(ecs, effect, target),
+        EffectType::Feed { nutrition } => food::feed(ecs, target, *nutrition),
+        EffectType::MagicMapping => {
+            *ecs.write_resource::<crate::RunState>() = crate::RunState::MagicMapReveal { row: 0 }
+        }
+        EffectType::SummonAlly => summon::summon_ally(ecs, target),
+        EffectType::RemoveCurse => curse::remove_curse(ecs, target),
+        EffectType::Recharge => charges::recharge(ecs, target),
+        EffectType::LearnSpell { spell } => spells::learn_spell(ecs, target, spell),
+        EffectType::Teleport => traps::teleport(ecs, target),
+        EffectType::Alarm => traps::alarm(ecs),
+        EffectType::Trapdoor => traps::trapdoor(ecs, target),
+        EffectType::Particle { .. } => {
+            if let Some(tile_idx) = entity_position(ecs, target) {
+                particles::particle_to_tile(ecs, tile_idx, effect);
+            }
+        }
+        EffectType::ItemUse { .. } | EffectType::TriggerFire { .. } => {}
+    }
+}
+
+/// Every map tile covered by `targets`; single-entity targets cover the tile
+/// the entity stands on.
+pub fn target_tiles(ecs: &World, targets: &Targets) -> Vec<i32> {
+    match targets {
+        Targets::Single { target } => entity_position(ecs, *target).into_iter().collect(),
+        Targets::Tile { tile_idx } => vec![*tile_idx],
+        Targets::Tiles { tiles } => tiles.clone(),
+        Targets::Area { center, radius } => {
+            let map = ecs.fetch::<Map>();
+            let center = Point::new(center % map.width, center / map.width);
+            field_of_view(center, *radius, &*map)
+                .iter()
+                .filter(|p| p.x > 0 && p.x < map.width - 1 && p.y > 0 && p.y < map.height - 1)
+                .map(|p| map.xy_idx(p.x, p.y) as i32)
+                .collect()
+        }
+    }
+}
+
+/// Every entity that `targets` would affect.
+pub fn target_entities(ecs: &World, targets: &Targets) -> Vec<Entity> {
+    match targets {
+        Targets::Single { target } => vec![*target],
+        _ => {
+            let map = ecs.fetch::<Map>();
+            target_tiles(ecs, targets)
+                .iter()
+                .flat_map(|idx| map.tile_content[*idx as usize].iter().cloned())
+                .collect()
+        }
+    }
+}
+
+pub fn entity_position(ecs: &World, target: Entity) -> Option<i32> {
+    let map = ecs.fetch::<Map>();
+    ecs.read_storage::<Position>()
+        .get(target)
+        .map(|pos| map.xy_idx(pos.x, pos.y) as i32)
+}
@@ -0,0 +1,28 @@
+use crate::{gamelog::GameLog, Charges, InBackpack};
+use specs::prelude::*;
+
+/// Restores every wand and staff in `target`'s backpack to full charge.
+pub fn recharge(ecs: &mut World, target: Entity) {
+    let mut recharged = 0;
+    {
+        let backpack = ecs.read_storage::<InBackpack>();
+        let mut charges = ecs.write_storage::<Charges>();
+        for (charges, carried) in (&mut charges, &backpack).join() {
+            if carried.owner == target && charges.uses < charges.max_uses {
+                charges.uses = charges.max_uses;
+                recharged += 1;
+            }
+        }
+    }
+
+    if target == *ecs.fetch::<Entity>() {
+        let message = if recharged > 0 {
+            "Your pack hums with renewed power."
+        } else {
+            "You feel a brief tingle, and nothing more."
+        };
+        ecs.write_resource::<GameLog>()
+            .entries
+            .push(message.to_string());
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{gamelog::GameLog, Cursed, Equipped, InBackpack};
+use specs::prelude::*;
+
+/// Lifts the curse from everything `target` carries or wears.
+pub fn remove_curse(ecs: &mut World, target: Entity) {
+    let lifted: Vec<Entity> = {
+        let entities = ecs.entities();
+        let cursed = ecs.read_storage::<Cursed>();
+        let equipped = ecs.read_storage::<Equipped>();
+        let backpack = ecs.read_storage::<InBackpack>();
+        (&entities, &cursed)
+            .join()
+            .filter(|(item, _curse)| {
+                equipped.get(*item).is_some_and(|e| e.owner == target)
+                    || backpack.get(*item).is_some_and(|b| b.owner == target)
+            })
+            .map(|(item, _curse)| item)
+            .collect()
+    };
+
+    let mut cursed = ecs.write_storage::<Cursed>();
+    for item in lifted.iter() {
+        cursed.remove(*item);
+    }
+    if target == *ecs.fetch::<Entity>() {
+        let message = if lifted.is_empty() {
+            "You feel as if someone is watching over you."
+        } else {
+            "You feel a malevolent weight lift from your belongings."
+        };
+        ecs.write_resource::<GameLog>()
+            .entries
+            .push(message.to_string());
+    }
+}
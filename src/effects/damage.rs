@@ -0,0 +1,31 @@
+use super::{EffectSpawner, EffectType};
+use crate::{CombatStats, SufferDamage};
+use specs::prelude::*;
+
+pub fn inflict_damage(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
+    if let EffectType::Damage {
+        amount,
+        damage_type,
+        weapon,
+    } = effect.effect_type
+    {
+        if ecs.read_storage::<CombatStats>().get(target).is_some() {
+            SufferDamage::new_damage(
+                &mut ecs.write_storage::<SufferDamage>(),
+                target,
+                amount,
+                damage_type,
+                effect.creator,
+                weapon,
+            );
+        }
+    }
+}
+
+pub fn heal_damage(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
+    if let EffectType::Healing { amount } = effect.effect_type {
+        if let Some(stats) = ecs.write_storage::<CombatStats>().get_mut(target) {
+            stats.hp = i32::min(stats.max_hp, stats.hp + amount);
+        }
+    }
+}
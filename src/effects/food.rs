@@ -0,0 +1,20 @@
+use crate::{gamelog::GameLog, gamesystem::MAX_SATIATION, HungerClock};
+use specs::prelude::*;
+
+pub fn feed(ecs: &mut World, target: Entity, nutrition: i32) {
+    let mut clocks = ecs.write_storage::<HungerClock>();
+    let Some(clock) = clocks.get_mut(target) else {
+        return;
+    };
+    clock.satiation += nutrition;
+
+    // Stuffing yourself past bursting brings the whole lot back up
+    if clock.satiation > MAX_SATIATION {
+        clock.satiation = MAX_SATIATION / 4;
+        if target == *ecs.fetch::<Entity>() {
+            ecs.write_resource::<GameLog>()
+                .entries
+                .push("You eat far too much, and throw up!".to_string());
+        }
+    }
+}
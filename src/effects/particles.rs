@@ -0,0 +1,23 @@
+use super::{EffectSpawner, EffectType};
+use crate::{Map, ParticleBuilder};
+use specs::prelude::*;
+
+pub fn particle_to_tile(ecs: &mut World, tile_idx: i32, effect: &EffectSpawner) {
+    if let EffectType::Particle {
+        glyph,
+        fg,
+        bg,
+        lifetime,
+    } = effect.effect_type
+    {
+        let width = ecs.fetch::<Map>().width;
+        ecs.write_resource::<ParticleBuilder>().request(
+            tile_idx % width,
+            tile_idx / width,
+            fg,
+            bg,
+            glyph,
+            lifetime,
+        );
+    }
+}
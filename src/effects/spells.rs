@@ -0,0 +1,11 @@
+use crate::{gamelog::GameLog, spawner};
+use specs::prelude::*;
+
+pub fn learn_spell(ecs: &mut World, target: Entity, spell: &str) {
+    spawner::learn_spell(ecs, target, spell);
+    if target == *ecs.fetch::<Entity>() {
+        ecs.write_resource::<GameLog>()
+            .entries
+            .push(format!("You learn to cast {}.", spell));
+    }
+}
@@ -0,0 +1,26 @@
+use super::{EffectSpawner, EffectType};
+use crate::{CombatStats, StatusEffect, StatusEffects};
+use specs::prelude::*;
+
+pub fn apply_status(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
+    if let EffectType::Status {
+        kind,
+        turns,
+        potency,
+    } = effect.effect_type
+    {
+        // Only living things can be poisoned, hasted and so on
+        if ecs.read_storage::<CombatStats>().get(target).is_none() {
+            return;
+        }
+        StatusEffects::apply(
+            &mut ecs.write_storage::<StatusEffects>(),
+            target,
+            StatusEffect {
+                kind,
+                turns,
+                potency,
+            },
+        );
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{spawner, Map, Position, TileType};
+use specs::prelude::*;
+
+/// A free floor tile next to `target`, where something could be summoned.
+pub fn free_tile_near(ecs: &World, target: Entity) -> Option<(i32, i32)> {
+    let map = ecs.fetch::<Map>();
+    let positions = ecs.read_storage::<Position>();
+    positions.get(target).and_then(|pos| {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (pos.x + dx, pos.y + dy)))
+            .find(|(x, y)| {
+                let idx = map.xy_idx(*x, *y);
+                (*x, *y) != (pos.x, pos.y) && map.tiles[idx] != TileType::Wall && !map.blocked[idx]
+            })
+    })
+}
+
+pub fn summon_ally(ecs: &mut World, target: Entity) {
+    if let Some((x, y)) = free_tile_near(ecs, target) {
+        let lazy = ecs.read_resource::<LazyUpdate>();
+        spawner::summoned_warrior(&lazy, &ecs.entities(), x, y);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{gamelog::GameLog, Alertness, Map, Position, RunState, TileType, Viewshed};
+use bracket_lib::prelude::{Point, RandomNumberGenerator};
+use specs::prelude::*;
+
+/// Whisks `target` away to a random open floor tile on the level.
+pub fn teleport(ecs: &mut World, target: Entity) {
+    let destination = {
+        let map = ecs.fetch::<Map>();
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        (0..100)
+            .map(|_| {
+                (
+                    rng.roll_dice(1, map.width - 2),
+                    rng.roll_dice(1, map.height - 2),
+                )
+            })
+            .find(|(x, y)| {
+                let idx = map.xy_idx(*x, *y);
+                map.tiles[idx] == TileType::Floor && !map.blocked[idx]
+            })
+    };
+    let Some((x, y)) = destination else {
+        return;
+    };
+
+    if let Some(pos) = ecs.write_storage::<Position>().get_mut(target) {
+        pos.x = x;
+        pos.y = y;
+    }
+    if let Some(viewshed) = ecs.write_storage::<Viewshed>().get_mut(target) {
+        viewshed.dirty = true;
+    }
+    let player_entity = *ecs.fetch::<Entity>();
+    if target == player_entity {
+        *ecs.write_resource::<Point>() = Point::new(x, y);
+        ecs.write_resource::<GameLog>()
+            .entries
+            .push("The world lurches around you!".to_string());
+    }
+}
+
+/// Wakes up everything on the level.
+pub fn alarm(ecs: &mut World) {
+    ecs.write_storage::<Alertness>().clear();
+    ecs.write_resource::<GameLog>()
+        .entries
+        .push("A shrill alarm echoes through the level!".to_string());
+}
+
+/// Drops the player through the floor to the next depth.
+pub fn trapdoor(ecs: &mut World, target: Entity) {
+    if target != *ecs.fetch::<Entity>() {
+        return;
+    }
+    ecs.write_resource::<GameLog>()
+        .entries
+        .push("The floor gives way beneath you!".to_string());
+    *ecs.write_resource::<RunState>() = RunState::NextLevel;
+}
@@ -0,0 +1,332 @@
+use super::{summon, target_entities, EffectQueue, EffectType, Targets};
+use crate::{
+    gamelog::GameLog, gamesystem, identification::item_base_name, Alarm, AreaOfEffect, Attributes,
+    Charges, CombatStats, Consumable, Hidden, Identification, InflictsDamage, InflictsStatus,
+    KnownSpell, MagicMapper, Map, Name, NoiseBuilder, Position, ProvidesFood, ProvidesHealing,
+    Recharges, RemovesCurse, SingleActivation, Skill, Skills, Spellbook, Stack, SummonsAlly,
+    Teleports, Trapdoor,
+};
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+pub fn item_trigger(creator: Option<Entity>, item: Entity, targets: &Targets, ecs: &mut World) {
+    let Some(user) = creator else {
+        return;
+    };
+    let player_entity = *ecs.fetch::<Entity>();
+    let affected: Vec<Entity> = {
+        let combat_stats = ecs.read_storage::<CombatStats>();
+        target_entities(ecs, targets)
+            .into_iter()
+            .filter(|e| combat_stats.get(*e).is_some())
+            .collect()
+    };
+
+    // Using something tells you what it was
+    if user == player_entity {
+        let name = ecs.read_storage::<Name>().get(item).unwrap().name.clone();
+        ecs.write_resource::<Identification>().identify(&name);
+    }
+
+    // Wands and staves that have run dry do nothing at all
+    if ecs
+        .read_storage::<Charges>()
+        .get(item)
+        .is_some_and(|charges| charges.uses < 1)
+    {
+        if user == player_entity {
+            ecs.write_resource::<GameLog>()
+                .entries
+                .push("Nothing happens; it is out of charges.".to_string());
+        }
+        return;
+    }
+
+    let mut effects: Vec<(EffectType, Targets)> = Vec::new();
+    let mut log: Vec<String> = Vec::new();
+    let mut consumed = true;
+    {
+        let names = ecs.read_storage::<Name>();
+        let item_name = &item_base_name(ecs, item);
+        let user_name = &names.get(user).unwrap().name;
+        let user_visible = is_visible(ecs, user);
+
+        // The whole blast lights up
+        if ecs.read_storage::<AreaOfEffect>().get(item).is_some() {
+            effects.push((particle(to_cp437('░'), RGB::named(ORANGE)), targets.clone()));
+        }
+
+        if let Some(healer) = ecs.read_storage::<ProvidesHealing>().get(item) {
+            effects.push((
+                EffectType::Healing {
+                    amount: healer.heal_amount,
+                },
+                targets.clone(),
+            ));
+            effects.push((particle(to_cp437('♥'), RGB::named(GREEN)), targets.clone()));
+            if user == player_entity && affected.contains(&player_entity) {
+                log.push(format!(
+                    "You use the {}, healing {} hp.",
+                    item_name, healer.heal_amount
+                ));
+            } else if user_visible {
+                log.push(format!("The {} drinks the {}.", user_name, item_name));
+            }
+        }
+
+        if let Some(food) = ecs.read_storage::<ProvidesFood>().get(item) {
+            effects.push((
+                EffectType::Feed {
+                    nutrition: food.nutrition,
+                },
+                targets.clone(),
+            ));
+            if user == player_entity {
+                log.push(format!("You eat the {}.", item_name));
+            } else if user_visible {
+                log.push(format!("The {} eats the {}.", user_name, item_name));
+            }
+        }
+
+        if let Some(damage) = ecs.read_storage::<InflictsDamage>().get(item) {
+            // Those versed in magic get more out of a scroll
+            let mut amount = damage.damage
+                + gamesystem::skill_level(ecs.read_storage::<Skills>().get(user), Skill::Magic);
+            if let Some(attrs) = ecs.read_storage::<Attributes>().get(user) {
+                amount += attrs.intelligence.bonus;
+            }
+            let amount = i32::max(0, amount);
+            effects.push((
+                EffectType::Damage {
+                    amount,
+                    damage_type: damage.damage_type,
+                    weapon: false,
+                },
+                targets.clone(),
+            ));
+            if affected.is_empty() {
+                consumed = false;
+            }
+            for mob in affected.iter() {
+                effects.push((
+                    particle(to_cp437('‼'), RGB::named(RED)),
+                    Targets::Single { target: *mob },
+                ));
+                if user == player_entity {
+                    log.push(format!(
+                        "You use {} on {}, inflicting {} hp.",
+                        item_name,
+                        names.get(*mob).unwrap().name,
+                        amount
+                    ));
+                } else if *mob == player_entity {
+                    log.push(format!(
+                        "The {} uses {} on you, inflicting {} hp.",
+                        user_name, item_name, amount
+                    ));
+                }
+            }
+        }
+
+        if let Some(status) = ecs.read_storage::<InflictsStatus>().get(item) {
+            effects.push((
+                EffectType::Status {
+                    kind: status.kind,
+                    turns: status.turns,
+                    potency: status.potency,
+                },
+                targets.clone(),
+            ));
+            if affected.is_empty() {
+                consumed = false;
+            }
+            for mob in affected.iter() {
+                effects.push((
+                    particle(to_cp437('?'), RGB::named(MAGENTA)),
+                    Targets::Single { target: *mob },
+                ));
+                if *mob == player_entity {
+                    log.push(format!("You are {}!", status.kind.name()));
+                } else if user == player_entity {
+                    log.push(format!(
+                        "You use {} on {}, leaving it {}.",
+                        item_name,
+                        names.get(*mob).unwrap().name,
+                        status.kind.name()
+                    ));
+                }
+            }
+        }
+
+        if ecs.read_storage::<SummonsAlly>().get(item).is_some() {
+            if summon::free_tile_near(ecs, user).is_some() {
+                effects.push((EffectType::SummonAlly, Targets::Single { target: user }));
+                if user == player_entity {
+                    log.push("A warrior answers your call!".to_string());
+                }
+            } else {
+                consumed = false;
+                if user == player_entity {
+                    log.push("There is no room for anyone to answer your call.".to_string());
+                }
+            }
+        }
+
+        if ecs.read_storage::<RemovesCurse>().get(item).is_some() {
+            effects.push((EffectType::RemoveCurse, Targets::Single { target: user }));
+        }
+
+        if ecs.read_storage::<Recharges>().get(item).is_some() {
+            effects.push((EffectType::Recharge, Targets::Single { target: user }));
+        }
+
+        if let Some(book) = ecs.read_storage::<Spellbook>().get(item) {
+            let known = (&ecs.read_storage::<KnownSpell>(), &names)
+                .join()
+                .any(|(spell, name)| spell.owner == user && name.name == book.spell);
+            if known {
+                consumed = false;
+                if user == player_entity {
+                    log.push(format!("You already know how to cast {}.", book.spell));
+                }
+            } else {
+                effects.push((
+                    EffectType::LearnSpell {
+                        spell: book.spell.clone(),
+                    },
+                    Targets::Single { target: user },
+                ));
+            }
+        }
+
+        if ecs.read_storage::<MagicMapper>().get(item).is_some() {
+            effects.push((EffectType::MagicMapping, Targets::Single { target: user }));
+            log.push("The map is revealed to you!".to_string());
+        }
+    }
+
+    ecs.write_resource::<GameLog>().entries.extend(log);
+    let mut queue = ecs.write_resource::<EffectQueue>();
+    for (effect_type, targets) in effects {
+        queue.add_effect(creator, effect_type, targets);
+    }
+
+    if let (true, Some(charges)) = (consumed, ecs.write_storage::<Charges>().get_mut(item)) {
+        charges.uses -= 1;
+        if charges.uses < 1 && user == player_entity {
+            ecs.write_resource::<GameLog>()
+                .entries
+                .push("It crackles and goes dark.".to_string());
+        }
+    }
+    if consumed && ecs.read_storage::<Consumable>().get(item).is_some() {
+        let mut stacks = ecs.write_storage::<Stack>();
+        match stacks.get_mut(item) {
+            Some(stack) if stack.count > 2 => stack.count -= 1,
+            Some(_) => {
+                stacks.remove(item);
+            }
+            None => ecs.entities().delete(item).expect("Delete failed"),
+        }
+    }
+}
+
+pub fn trap_trigger(trigger: Entity, targets: &Targets, ecs: &mut World) {
+    if let Some(name) = ecs.read_storage::<Name>().get(trigger) {
+        ecs.write_resource::<GameLog>()
+            .entries
+            .push(format!("{} triggers!", &name.name));
+    }
+    ecs.write_storage::<Hidden>().remove(trigger);
+    let trap_pos = ecs
+        .read_storage::<Position>()
+        .get(trigger)
+        .map(|pos| (pos.x, pos.y));
+    if let Some((x, y)) = trap_pos {
+        ecs.write_resource::<NoiseBuilder>().request(x, y, 6);
+    }
+
+    // Gas traps fill the area around them, catching more than whoever stepped in
+    let area = ecs.read_storage::<AreaOfEffect>().get(trigger).cloned();
+    let targets = match (area, trap_pos) {
+        (Some(area), Some((x, y))) => &Targets::Area {
+            center: ecs.fetch::<Map>().xy_idx(x, y) as i32,
+            radius: area.radius,
+        },
+        _ => targets,
+    };
+
+    {
+        let mut queue = ecs.write_resource::<EffectQueue>();
+        if let Targets::Area { .. } = targets {
+            queue.add_effect(
+                None,
+                particle(to_cp437('░'), RGB::named(MAGENTA)),
+                targets.clone(),
+            );
+        }
+        if let Some(damage) = ecs.read_storage::<InflictsDamage>().get(trigger) {
+            queue.add_effect(
+                None,
+                particle(to_cp437('‼'), RGB::named(ORANGE)),
+                targets.clone(),
+            );
+            queue.add_effect(
+                None,
+                EffectType::Damage {
+                    amount: damage.damage,
+                    damage_type: damage.damage_type,
+                    weapon: false,
+                },
+                targets.clone(),
+            );
+        }
+        if let Some(status) = ecs.read_storage::<InflictsStatus>().get(trigger) {
+            queue.add_effect(
+                None,
+                EffectType::Status {
+                    kind: status.kind,
+                    turns: status.turns,
+                    potency: status.potency,
+                },
+                targets.clone(),
+            );
+        }
+        if ecs.read_storage::<Teleports>().get(trigger).is_some() {
+            queue.add_effect(None, EffectType::Teleport, targets.clone());
+        }
+        if ecs.read_storage::<Alarm>().get(trigger).is_some() {
+            queue.add_effect(None, EffectType::Alarm, targets.clone());
+        }
+        if ecs.read_storage::<Trapdoor>().get(trigger).is_some() {
+            queue.add_effect(None, EffectType::Trapdoor, targets.clone());
+        }
+    }
+
+    if ecs
+        .read_storage::<SingleActivation>()
+        .get(trigger)
+        .is_some()
+    {
+        ecs.entities()
+            .delete(trigger)
+            .expect("Failed to delete a trap.");
+    }
+}
+
+fn particle(glyph: FontCharType, fg: RGB) -> EffectType {
+    EffectType::Particle {
+        glyph,
+        fg,
+        bg: RGB::named(BLACK),
+        lifetime: 200.0,
+    }
+}
+
+fn is_visible(ecs: &World, entity: Entity) -> bool {
+    let map = ecs.fetch::<Map>();
+    match ecs.read_storage::<Position>().get(entity) {
+        Some(pos) => map.visible_tiles[map.xy_idx(pos.x, pos.y)],
+        None => false,
+    }
+}
@@ -0,0 +1,378 @@
+use super::{
+    AmmoType, Attributes, BlockChance, DamageReduction, DefenseBonus, EquipmentSlot, Equipped,
+    HungerState, InBackpack, Item, MeleeWeapon, RangedWeapon, Skill, Skills, Stack,
+};
+use bracket_lib::prelude::RandomNumberGenerator;
+use specs::prelude::*;
+
+/// Armor class of someone with no quickness, skill or armor to speak of.
+pub const BASE_ARMOR_CLASS: i32 = 10;
+
+/// What everyone hits for without a weapon in hand.
+pub const UNARMED_DAMAGE: &str = "1d6";
+
+/// How far anything can be thrown by hand.
+pub const THROW_RANGE: i32 = 6;
+
+/// To-hit penalty for the weapon swung in the off hand.
+pub const OFF_HAND_PENALTY: i32 = 2;
+
+/// Quickness lost while carrying more than you can manage.
+pub const OVERLOAD_PENALTY: i32 = 4;
+
+pub fn attr_bonus(value: i32) -> i32 {
+    (value - 10).div_euclid(2)
+}
+
+/// Pounds someone can carry before they are overloaded.
+pub fn carry_capacity(might: i32) -> f32 {
+    (15 + might * 5) as f32
+}
+
+/// Total weight of everything `owner` is carrying or wearing.
+pub fn carried_weight(
+    owner: Entity,
+    items: &ReadStorage<Item>,
+    backpack: &ReadStorage<InBackpack>,
+    equipped: &ReadStorage<Equipped>,
+    stacks: &ReadStorage<Stack>,
+) -> f32 {
+    let packed: f32 = (items, backpack, stacks.maybe())
+        .join()
+        .filter(|(_item, carried, _stack)| carried.owner == owner)
+        .map(|(item, _carried, stack)| item.weight * stack.map_or(1, |s| s.count) as f32)
+        .sum();
+    let worn: f32 = (items, equipped)
+        .join()
+        .filter(|(_item, worn)| worn.owner == owner)
+        .map(|(item, _worn)| item.weight)
+        .sum();
+    packed + worn
+}
+
+/// Eat past this and it all comes back up.
+pub const MAX_SATIATION: i32 = 2000;
+
+pub fn hunger_state(satiation: i32) -> HungerState {
+    match satiation {
+        s if s > 1500 => HungerState::Gorged,
+        s if s > 1000 => HungerState::WellFed,
+        s if s > 200 => HungerState::Normal,
+        s if s > 0 => HungerState::Hungry,
+        _ => HungerState::Starving,
+    }
+}
+
+pub fn player_hp_at_level(fitness: i32, level: i32) -> i32 {
+    20 + level * (10 + attr_bonus(fitness))
+}
+
+pub fn mana_at_level(intelligence: i32, level: i32) -> i32 {
+    4 + level * (2 + attr_bonus(intelligence))
+}
+
+/// Turns it takes to win back a point of mana; the clever and the practiced
+/// recover faster.
+pub fn mana_regen_turns(intelligence: i32, magic_skill: i32) -> i32 {
+    i32::max(2, 8 - attr_bonus(intelligence) - magic_skill)
+}
+
+/// Average turns between each hit point regained naturally.
+pub fn regen_interval(fitness: i32, level: i32) -> i32 {
+    i32::max(2, 12 - attr_bonus(fitness) * 2 - level / 2)
+}
+
+pub fn npc_hp(fitness: i32, level: i32) -> i32 {
+    8 + level * (8 + attr_bonus(fitness))
+}
+
+pub fn skill_level(skills: Option<&Skills>, skill: Skill) -> i32 {
+    match skills {
+        Some(skills) => *skills.skills.get(&skill).unwrap_or(&0),
+        None => 0,
+    }
+}
+
+/// How hard `target` is to hit: quickness, defense skill and worn armor.
+pub fn armor_class(
+    target: Entity,
+    skills: &ReadStorage<Skills>,
+    attributes: &ReadStorage<Attributes>,
+    defense_bonuses: &ReadStorage<DefenseBonus>,
+    equipped: &ReadStorage<Equipped>,
+) -> i32 {
+    let mut armor_class = BASE_ARMOR_CLASS + skill_level(skills.get(target), Skill::Defense);
+    if let Some(attrs) = attributes.get(target) {
+        armor_class += attrs.quickness.bonus;
+    }
+    for (defense_bonus, equipped_by) in (defense_bonuses, equipped).join() {
+        if equipped_by.owner == target {
+            armor_class += defense_bonus.defense;
+        }
+    }
+    armor_class
+}
+
+/// How much physical damage `target`'s armor soaks from every blow.
+pub fn damage_reduction(
+    target: Entity,
+    reductions: &ReadStorage<DamageReduction>,
+    equipped: &ReadStorage<Equipped>,
+) -> i32 {
+    (reductions, equipped)
+        .join()
+        .filter(|(_reduction, equipped_by)| equipped_by.owner == target)
+        .map(|(reduction, _equipped_by)| reduction.amount)
+        .sum()
+}
+
+/// The best shield `target` has ready, with its chance to block.
+pub fn best_block(
+    target: Entity,
+    entities: &Entities,
+    block_chances: &ReadStorage<BlockChance>,
+    equipped: &ReadStorage<Equipped>,
+) -> Option<(Entity, i32)> {
+    (entities, block_chances, equipped)
+        .join()
+        .filter(|(_shield, _block, equipped_by)| equipped_by.owner == target)
+        .map(|(shield, block, _equipped_by)| (shield, block.percent))
+        .max_by_key(|(_shield, percent)| *percent)
+}
+
+/// Rolls for `target` to block an incoming attack, returning the shield that
+/// stopped it.
+pub fn try_block(
+    target: Entity,
+    entities: &Entities,
+    block_chances: &ReadStorage<BlockChance>,
+    equipped: &ReadStorage<Equipped>,
+    rng: &mut RandomNumberGenerator,
+) -> Option<Entity> {
+    let (shield, percent) = best_block(target, entities, block_chances, equipped)?;
+    if rng.roll_dice(1, 100) <= percent {
+        Some(shield)
+    } else {
+        None
+    }
+}
+
+/// Parses dice strings such as "1d8", "2d6+1" or "1d4-1" into
+/// `(n_dice, die_type, bonus)`.
+pub fn parse_dice_string(dice: &str) -> (i32, i32, i32) {
+    let (n_dice, rest) = dice.split_once('d').expect("Dice string is missing a 'd'");
+    let (die_type, bonus) = match rest.find(['+', '-']) {
+        Some(split) => rest.split_at(split),
+        None => (rest, "0"),
+    };
+    (
+        n_dice.parse().expect("Invalid number of dice"),
+        die_type.parse().expect("Invalid die type"),
+        bonus
+            .trim_start_matches('+')
+            .parse()
+            .expect("Invalid dice bonus"),
+    )
+}
+
+pub fn melee_weapon(hit_bonus: i32, damage: &str) -> MeleeWeapon {
+    let (damage_n_dice, damage_die_type, damage_bonus) = parse_dice_string(damage);
+    MeleeWeapon {
+        hit_bonus,
+        damage_n_dice,
+        damage_die_type,
+        damage_bonus,
+    }
+}
+
+/// Experience awarded for killing something of the given level.
+pub fn xp_for_kill(level: i32) -> i32 {
+    level * 100
+}
+
+/// Total experience needed to advance beyond `level`.
+pub fn xp_to_level_up(level: i32) -> i32 {
+    level * 500
+}
+
+pub fn ranged_weapon(range: i32, hit_bonus: i32, damage: &str, ammo: AmmoType) -> RangedWeapon {
+    let (damage_n_dice, damage_die_type, damage_bonus) = parse_dice_string(damage);
+    RangedWeapon {
+        range,
+        hit_bonus,
+        damage_n_dice,
+        damage_die_type,
+        damage_bonus,
+        ammo,
+    }
+}
+
+/// Works out which slot an item goes into, given everything its wearer already
+/// has on as `(item, slot, two_handed)`, and which of those must come off to
+/// make room. A second ring goes on the other hand, and a one-handed weapon
+/// goes into a free off hand when the main hand is already armed.
+pub fn equip_slot(
+    slot: EquipmentSlot,
+    two_handed: bool,
+    is_weapon: bool,
+    worn: &[(Entity, EquipmentSlot, bool)],
+) -> (EquipmentSlot, Vec<Entity>) {
+    let is_free = |slot: EquipmentSlot| {
+        !worn.iter().any(|(_item, worn_slot, both_hands)| {
+            *worn_slot == slot || (*both_hands && slot == EquipmentSlot::Shield)
+        })
+    };
+    let slot = match slot {
+        EquipmentSlot::Ring1 | EquipmentSlot::Ring2 => {
+            if is_free(EquipmentSlot::Ring1) || !is_free(EquipmentSlot::Ring2) {
+                EquipmentSlot::Ring1
+            } else {
+                EquipmentSlot::Ring2
+            }
+        }
+        EquipmentSlot::Melee
+            if is_weapon
+                && !two_handed
+                && !is_free(EquipmentSlot::Melee)
+                && is_free(EquipmentSlot::Shield) =>
+        {
+            EquipmentSlot::Shield
+        }
+        slot => slot,
+    };
+
+    let mut occupies = vec![slot];
+    if two_handed {
+        occupies.push(EquipmentSlot::Shield);
+    }
+    let displaced = worn
+        .iter()
+        .filter(|(_item, worn_slot, both_hands)| {
+            occupies.contains(worn_slot)
+                || (*both_hands && occupies.contains(&EquipmentSlot::Shield))
+        })
+        .map(|(item, _slot, _both_hands)| *item)
+        .collect();
+    (slot, displaced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_dice_string_reads_bonus() {
+        assert_eq!(parse_dice_string("1d6"), (1, 6, 0));
+        assert_eq!(parse_dice_string("2d4+2"), (2, 4, 2));
+        assert_eq!(parse_dice_string("1d6-1"), (1, 6, -1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Dice string is missing a 'd'")]
+    fn parse_dice_string_rejects_missing_d() {
+        parse_dice_string("16");
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid dice bonus")]
+    fn parse_dice_string_rejects_bad_bonus() {
+        parse_dice_string("1d6+x");
+    }
+
+    fn items(count: usize) -> Vec<Entity> {
+        let mut world = World::new();
+        (0..count).map(|_| world.create_entity().build()).collect()
+    }
+
+    #[test]
+    fn equip_slot_two_handed_weapon_displaces_shield() {
+        let worn = items(2);
+        let (slot, displaced) = equip_slot(
+            EquipmentSlot::Melee,
+            true,
+            true,
+            &[
+                (worn[0], EquipmentSlot::Melee, false),
+                (worn[1], EquipmentSlot::Shield, false),
+            ],
+        );
+        assert!(slot == EquipmentSlot::Melee);
+        assert_eq!(displaced, worn);
+    }
+
+    #[test]
+    fn equip_slot_shield_displaces_two_handed_weapon() {
+        let worn = items(1);
+        let (slot, displaced) = equip_slot(
+            EquipmentSlot::Shield,
+            false,
+            false,
+            &[(worn[0], EquipmentSlot::Melee, true)],
+        );
+        assert!(slot == EquipmentSlot::Shield);
+        assert_eq!(displaced, worn);
+    }
+
+    #[test]
+    fn equip_slot_second_ring_goes_on_other_hand() {
+        let worn = items(2);
+        let (slot, displaced) = equip_slot(
+            EquipmentSlot::Ring1,
+            false,
+            false,
+            &[(worn[0], EquipmentSlot::Ring1, false)],
+        );
+        assert!(slot == EquipmentSlot::Ring2);
+        assert!(displaced.is_empty());
+
+        let (slot, displaced) = equip_slot(
+            EquipmentSlot::Ring1,
+            false,
+            false,
+            &[
+                (worn[0], EquipmentSlot::Ring1, false),
+                (worn[1], EquipmentSlot::Ring2, false),
+            ],
+        );
+        assert!(slot == EquipmentSlot::Ring1);
+        assert_eq!(displaced, vec![worn[0]]);
+    }
+
+    #[test]
+    fn equip_slot_second_weapon_goes_in_off_hand() {
+        let worn = items(2);
+        let (slot, displaced) = equip_slot(
+            EquipmentSlot::Melee,
+            false,
+            true,
+            &[(worn[0], EquipmentSlot::Melee, false)],
+        );
+        assert!(slot == EquipmentSlot::Shield);
+        assert!(displaced.is_empty());
+
+        let (slot, displaced) = equip_slot(
+            EquipmentSlot::Melee,
+            false,
+            true,
+            &[
+                (worn[0], EquipmentSlot::Melee, false),
+                (worn[1], EquipmentSlot::Shield, false),
+            ],
+        );
+        assert!(slot == EquipmentSlot::Melee);
+        assert_eq!(displaced, vec![worn[0]]);
+    }
+
+    #[test]
+    fn hunger_state_boundaries() {
+        assert!(hunger_state(1501) == HungerState::Gorged);
+        assert!(hunger_state(1500) == HungerState::WellFed);
+        assert!(hunger_state(1001) == HungerState::WellFed);
+        assert!(hunger_state(1000) == HungerState::Normal);
+        assert!(hunger_state(201) == HungerState::Normal);
+        assert!(hunger_state(200) == HungerState::Hungry);
+        assert!(hunger_state(1) == HungerState::Hungry);
+        assert!(hunger_state(0) == HungerState::Starving);
+        assert!(hunger_state(-50) == HungerState::Starving);
+    }
+}
@@ -1,8 +1,13 @@
-use crate::{InBackpack, Viewshed};
+use crate::{AlertState, Alertness, Ally, AllyOrder, InBackpack, StatusEffects, Viewshed};
 
 use super::{
-    gamelog::GameLog, rex_assets::RexAssets, CombatStats, Equipped, Hidden, HungerClock, Map, Name,
-    Player, Position, RunState, State,
+    gamelog::GameLog,
+    gamesystem::{armor_class, best_block, damage_reduction, skill_level, xp_to_level_up},
+    identification::{item_display_color, item_display_name},
+    rex_assets::RexAssets,
+    Attributes, BlockChance, CombatStats, DamageReduction, DefenseBonus, Encumbrance, Equipped,
+    Hidden, HungerClock, KnownSpell, Mana, Map, Name, Player, Position, RunState, Skill, Skills,
+    State,
 };
 use bracket_lib::prelude::*;
 use specs::prelude::*;
@@ -30,21 +35,64 @@ pub fn draw_ui(ecs: &World, ctx: &mut BTerm) {
     let combat_stats = ecs.read_storage::<CombatStats>();
     let players = ecs.read_storage::<Player>();
     let hunger = ecs.read_storage::<HungerClock>();
-    for (_player, stats, hunger_clock) in (&players, &combat_stats, &hunger).join() {
+    let statuses = ecs.read_storage::<StatusEffects>();
+    let mana = ecs.read_storage::<Mana>();
+    for (player_entity, _player, stats, hunger_clock) in
+        (&ecs.entities(), &players, &combat_stats, &hunger).join()
+    {
         let health = format!(" HP: {} / {} ", stats.hp, stats.max_hp);
         ctx.print_color(12, 43, RGB::named(YELLOW), RGB::named(BLACK), &health);
 
         ctx.draw_bar_horizontal(
             28,
             43,
-            51,
+            24,
             stats.hp,
             stats.max_hp,
             RGB::named(RED),
             RGB::named(BLACK),
         );
 
+        let level = format!(" Level: {} ", stats.level);
+        ctx.print_color(53, 43, RGB::named(YELLOW), RGB::named(BLACK), &level);
+        let level_floor = xp_to_level_up(stats.level - 1);
+        ctx.draw_bar_horizontal(
+            65,
+            43,
+            13,
+            stats.xp - level_floor,
+            xp_to_level_up(stats.level) - level_floor,
+            RGB::named(GOLD),
+            RGB::named(BLACK),
+        );
+
+        if let Some(pool) = mana.get(player_entity) {
+            let magic = format!(" MP: {} / {} ", pool.mana, pool.max_mana);
+            ctx.print_color(12, 49, RGB::named(CYAN), RGB::named(BLACK), &magic);
+            ctx.draw_bar_horizontal(
+                28,
+                49,
+                24,
+                pool.mana,
+                pool.max_mana,
+                RGB::named(BLUE),
+                RGB::named(BLACK),
+            );
+        }
+
+        if let Some(status) = statuses.get(player_entity) {
+            let mut x = 2;
+            for effect in status.effects.iter() {
+                let label = format!("{} ({})", effect.kind.name(), effect.turns);
+                ctx.print_color(x, 42, RGB::named(CYAN), RGB::named(BLACK), &label);
+                x += label.len() as i32 + 2;
+            }
+        }
+
         match hunger_clock.state {
+            crate::HungerState::Gorged => {
+                ctx.print_color(71, 42, RGB::named(YELLOW), RGB::named(BLACK), "Gorged")
+            }
             crate::HungerState::WellFed => {
                 ctx.print_color(71, 42, RGB::named(GREEN), RGB::named(BLACK), "Well Fed")
             }
@@ -58,13 +106,15 @@ pub fn draw_ui(ecs: &World, ctx: &mut BTerm) {
         }
     }
 
+    draw_allies(ecs, ctx);
+    draw_spells(ecs, ctx);
+
     let log = ecs.fetch::<GameLog>();
-    let mut y = 44;
-    for s in log.entries.iter().rev() {
+    for (i, s) in log.entries.iter().rev().enumerate() {
+        let y = 44 + i as i32;
         if y < 49 {
             ctx.print(2, y, s);
         }
-        y += 1;
     }
 
     let mouse_pos = ctx.mouse_pos();
@@ -73,28 +123,140 @@ pub fn draw_ui(ecs: &World, ctx: &mut BTerm) {
     draw_tooltip(ecs, ctx);
 }
 
+fn draw_allies(ecs: &World, ctx: &mut BTerm) {
+    let allies = ecs.read_storage::<Ally>();
+    let names = ecs.read_storage::<Name>();
+    let combat_stats = ecs.read_storage::<CombatStats>();
+
+    let party: Vec<(&Ally, &Name, &CombatStats)> =
+        (&allies, &names, &combat_stats).join().collect();
+    if party.is_empty() {
+        return;
+    }
+
+    ctx.draw_box(
+        57,
+        0,
+        22,
+        party.len() + 1,
+        RGB::named(WHITE),
+        RGB::named(BLACK),
+    );
+    ctx.print_color(59, 0, RGB::named(YELLOW), RGB::named(BLACK), "Allies");
+    for (i, (ally, name, stats)) in party.iter().enumerate() {
+        let y = 1 + i as i32;
+        let order = match ally.order {
+            AllyOrder::Follow => "follow",
+            AllyOrder::Stay => "stay",
+            AllyOrder::Attack => "attack",
+        };
+        let health_color = if stats.hp * 3 < stats.max_hp {
+            RGB::named(RED)
+        } else {
+            RGB::named(GREEN)
+        };
+        ctx.print(58, y, &name.name);
+        ctx.print_color(
+            67,
+            y,
+            health_color,
+            RGB::named(BLACK),
+            format!("{}/{}", stats.hp, stats.max_hp),
+        );
+        ctx.print_color(73, y, RGB::named(CYAN), RGB::named(BLACK), order);
+    }
+}
+
+fn draw_spells(ecs: &World, ctx: &mut BTerm) {
+    let player_entity = ecs.fetch::<Entity>();
+    let known_spells = ecs.read_storage::<KnownSpell>();
+    let names = ecs.read_storage::<Name>();
+    let mana = ecs
+        .read_storage::<Mana>()
+        .get(*player_entity)
+        .map_or(0, |m| m.mana);
+
+    let spells: Vec<(&KnownSpell, &Name)> = (&known_spells, &names)
+        .join()
+        .filter(|(spell, _name)| spell.owner == *player_entity)
+        .collect();
+    if spells.is_empty() {
+        return;
+    }
+
+    ctx.draw_box(
+        0,
+        0,
+        22,
+        spells.len() + 1,
+        RGB::named(WHITE),
+        RGB::named(BLACK),
+    );
+    ctx.print_color(2, 0, RGB::named(YELLOW), RGB::named(BLACK), "Spells");
+    for (i, (spell, name)) in spells.iter().enumerate() {
+        let y = 1 + i as i32;
+        let color = if spell.mana_cost <= mana {
+            RGB::named(CYAN)
+        } else {
+            RGB::named(GREY)
+        };
+        ctx.print_color(1, y, color, RGB::named(BLACK), &name.name);
+        ctx.print_color(
+            17,
+            y,
+            color,
+            RGB::named(BLACK),
+            format!("{}mp", spell.mana_cost),
+        );
+    }
+}
+
 fn draw_tooltip(ecs: &World, ctx: &mut BTerm) {
     let map = ecs.fetch::<Map>();
     let names = ecs.read_storage::<Name>();
     let positions = ecs.read_storage::<Position>();
     let hidden = ecs.read_storage::<Hidden>();
+    let alertness = ecs.read_storage::<Alertness>();
+    let allies = ecs.read_storage::<Ally>();
+    let statuses = ecs.read_storage::<StatusEffects>();
     const TOOLTIP_BG: (u8, u8, u8) = (100, 100, 100);
 
     let mouse_pos = ctx.mouse_pos();
     if mouse_pos.0 >= map.width || mouse_pos.1 >= map.height {
         return;
     }
-    let mut tooltip: Vec<String> = Vec::new();
-    for (name, position, _hidden) in (&names, &positions, !&hidden).join() {
+    let mut tooltip: Vec<(String, RGB)> = Vec::new();
+    for (entity, _name, position, _hidden) in (&ecs.entities(), &names, &positions, !&hidden).join()
+    {
         let idx = map.xy_idx(position.x, position.y);
         if position.x == mouse_pos.0 && position.y == mouse_pos.1 && map.visible_tiles[idx] {
-            tooltip.push(name.name.to_string());
+            let mut tags: Vec<&str> = Vec::new();
+            match alertness.get(entity) {
+                None if allies.get(entity).is_some() => tags.push("ally"),
+                None => {}
+                Some(alert) => match alert.state {
+                    AlertState::Asleep => tags.push("sleeping"),
+                    AlertState::Unaware => tags.push("unaware"),
+                },
+            }
+            if let Some(status) = statuses.get(entity) {
+                tags.extend(status.effects.iter().map(|e| e.kind.name()));
+            }
+            let color = item_display_color(ecs, entity);
+            if tags.is_empty() {
+                tooltip.push((item_display_name(ecs, entity), color));
+            } else {
+                tooltip.push((
+                    format!("{} ({})", item_display_name(ecs, entity), tags.join(", ")),
+                    color,
+                ));
+            }
         }
     }
 
     if !tooltip.is_empty() {
         let mut width = 0;
-        for s in tooltip.iter() {
+        for (s, _color) in tooltip.iter() {
             if width < s.len() as i32 {
                 width = s.len() as i32
             }
@@ -104,9 +266,9 @@ fn draw_tooltip(ecs: &World, ctx: &mut BTerm) {
         if mouse_pos.0 > 40 {
             let arrow_pos = Point::new(mouse_pos.0 - 2, mouse_pos.1);
             let left_x = mouse_pos.0 - width;
-            let mut y = mouse_pos.1;
-            for s in tooltip.iter() {
-                ctx.print_color(left_x, y, RGB::named(WHITE), RGB::named(TOOLTIP_BG), s);
+            for (i, (s, color)) in tooltip.iter().enumerate() {
+                let y = mouse_pos.1 + i as i32;
+                ctx.print_color(left_x, y, *color, RGB::named(TOOLTIP_BG), s);
                 let padding = (width - s.len() as i32) - 1;
                 for i in 0..padding {
                     ctx.print_color(
@@ -114,24 +276,23 @@ fn draw_tooltip(ecs: &World, ctx: &mut BTerm) {
                         y,
                         RGB::named(WHITE),
                         RGB::from_f32(0.2, 0.2, 0.2),
-                        &" ".to_string(),
+                        " ",
                     );
                 }
-                y += 1;
             }
             ctx.print_color(
                 arrow_pos.x,
                 arrow_pos.y,
                 RGB::named(WHITE),
                 RGB::named(TOOLTIP_BG),
-                &"->".to_string(),
+                "->",
             );
         } else {
             let arrow_pos = Point::new(mouse_pos.0 + 1, mouse_pos.1);
             let left_x = mouse_pos.0 + 3;
-            let mut y = mouse_pos.1;
-            for s in tooltip.iter() {
-                ctx.print_color(left_x + 1, y, RGB::named(WHITE), RGB::named(TOOLTIP_BG), s);
+            for (i, (s, color)) in tooltip.iter().enumerate() {
+                let y = mouse_pos.1 + i as i32;
+                ctx.print_color(left_x + 1, y, *color, RGB::named(TOOLTIP_BG), s);
                 let padding = (width - s.len() as i32) - 1;
                 for i in 0..padding {
                     ctx.print_color(
@@ -139,17 +300,16 @@ fn draw_tooltip(ecs: &World, ctx: &mut BTerm) {
                         y,
                         RGB::named(WHITE),
                         RGB::named(TOOLTIP_BG),
-                        &" ".to_string(),
+                        " ",
                     );
                 }
-                y += 1;
             }
             ctx.print_color(
                 arrow_pos.x,
                 arrow_pos.y,
                 RGB::named(WHITE),
                 RGB::named(TOOLTIP_BG),
-                &"<-".to_string(),
+                "<-",
             );
         }
     }
@@ -173,7 +333,7 @@ pub fn show_inventory(game_state: &mut State, ctx: &mut BTerm) -> (ItemMenuResul
         .filter(|item| item.0.owner == *player_entity);
     let count = inventory.count();
 
-    let mut y = (25 - (count / 2)) as i32;
+    let y = (25 - (count / 2)) as i32;
     ctx.draw_box(
         15,
         y - 2,
@@ -189,6 +349,24 @@ pub fn show_inventory(game_state: &mut State, ctx: &mut BTerm) -> (ItemMenuResul
         RGB::named(BLACK),
         "Inventory",
     );
+    if let Some(burden) = game_state
+        .ecs
+        .read_storage::<Encumbrance>()
+        .get(*player_entity)
+    {
+        let color = if burden.overloaded() {
+            RGB::named(RED)
+        } else {
+            RGB::named(YELLOW)
+        };
+        ctx.print_color(
+            30,
+            y - 2,
+            color,
+            RGB::named(BLACK),
+            format!("{:.1}/{:.0} lbs", burden.weight, burden.capacity),
+        );
+    }
     ctx.print_color(
         18,
         y + count as i32 + 1,
@@ -198,11 +376,12 @@ pub fn show_inventory(game_state: &mut State, ctx: &mut BTerm) -> (ItemMenuResul
     );
 
     let mut equipable: Vec<Entity> = Vec::new();
-    let mut j = 0;
-    for (entity, _pack, name) in (&entities, &backpack, &names)
+    for (j, (entity, _pack, _name)) in (&entities, &backpack, &names)
         .join()
         .filter(|item| item.1.owner == *player_entity)
+        .enumerate()
     {
+        let y = y + j as i32;
         ctx.set(17, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437('('));
         ctx.set(
             18,
@@ -213,10 +392,14 @@ pub fn show_inventory(game_state: &mut State, ctx: &mut BTerm) -> (ItemMenuResul
         );
         ctx.set(19, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437(')'));
 
-        ctx.print(21, y, &name.name.to_string());
+        ctx.print_color(
+            21,
+            y,
+            item_display_color(&game_state.ecs, entity),
+            RGB::named(BLACK),
+            item_display_name(&game_state.ecs, entity),
+        );
         equipable.push(entity);
-        y += 1;
-        j += 1;
     }
 
     match ctx.key {
@@ -238,6 +421,21 @@ pub fn show_inventory(game_state: &mut State, ctx: &mut BTerm) -> (ItemMenuResul
 }
 
 pub fn drop_item_menu(game_state: &mut State, ctx: &mut BTerm) -> (ItemMenuResult, Option<Entity>) {
+    backpack_menu(game_state, ctx, "Drop Which Item?")
+}
+
+pub fn throw_item_menu(
+    game_state: &mut State,
+    ctx: &mut BTerm,
+) -> (ItemMenuResult, Option<Entity>) {
+    backpack_menu(game_state, ctx, "Throw Which Item?")
+}
+
+fn backpack_menu(
+    game_state: &mut State,
+    ctx: &mut BTerm,
+    title: &str,
+) -> (ItemMenuResult, Option<Entity>) {
     let player_entity = game_state.ecs.fetch::<Entity>();
     let names = game_state.ecs.read_storage::<Name>();
     let backpack = game_state.ecs.read_storage::<InBackpack>();
@@ -248,7 +446,7 @@ pub fn drop_item_menu(game_state: &mut State, ctx: &mut BTerm) -> (ItemMenuResul
         .filter(|item| item.0.owner == *player_entity);
     let count = inventory.count();
 
-    let mut y = (25 - (count / 2)) as i32;
+    let y = (25 - (count / 2)) as i32;
     ctx.draw_box(
         15,
         y - 2,
@@ -257,13 +455,7 @@ pub fn drop_item_menu(game_state: &mut State, ctx: &mut BTerm) -> (ItemMenuResul
         RGB::named(WHITE),
         RGB::named(BLACK),
     );
-    ctx.print_color(
-        18,
-        y - 2,
-        RGB::named(YELLOW),
-        RGB::named(BLACK),
-        "Drop Which Item?",
-    );
+    ctx.print_color(18, y - 2, RGB::named(YELLOW), RGB::named(BLACK), title);
     ctx.print_color(
         18,
         y + count as i32 + 1,
@@ -273,11 +465,12 @@ pub fn drop_item_menu(game_state: &mut State, ctx: &mut BTerm) -> (ItemMenuResul
     );
 
     let mut equippable: Vec<Entity> = Vec::new();
-    let mut j = 0;
-    for (entity, _pack, name) in (&entities, &backpack, &names)
+    for (j, (entity, _pack, _name)) in (&entities, &backpack, &names)
         .join()
         .filter(|item| item.1.owner == *player_entity)
+        .enumerate()
     {
+        let y = y + j as i32;
         ctx.set(17, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437('('));
         ctx.set(
             18,
@@ -288,10 +481,14 @@ pub fn drop_item_menu(game_state: &mut State, ctx: &mut BTerm) -> (ItemMenuResul
         );
         ctx.set(19, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437(')'));
 
-        ctx.print(21, y, &name.name.to_string());
+        ctx.print_color(
+            21,
+            y,
+            item_display_color(&game_state.ecs, entity),
+            RGB::named(BLACK),
+            item_display_name(&game_state.ecs, entity),
+        );
         equippable.push(entity);
-        y += 1;
-        j += 1;
     }
 
     match ctx.key {
@@ -343,6 +540,10 @@ pub fn ranged_target(
         return (ItemMenuResult::Cancel, None);
     }
 
+    if ctx.key == Some(VirtualKeyCode::Escape) {
+        return (ItemMenuResult::Cancel, None);
+    }
+
     let mouse_pos = ctx.mouse_pos();
     let mut valid_target = false;
     for idx in available_cells.iter() {
@@ -482,11 +683,11 @@ pub fn remove_item_menu(gs: &mut State, ctx: &mut BTerm) -> (ItemMenuResult, Opt
         .filter(|item| item.0.owner == *player_entity);
     let count = inventory.count();
 
-    let mut y = (25 - (count / 2)) as i32;
+    let y = (25 - (count / 2)) as i32;
     ctx.draw_box(
         15,
         y - 2,
-        31,
+        45,
         count + 3,
         RGB::named(WHITE),
         RGB::named(BLACK),
@@ -507,11 +708,12 @@ pub fn remove_item_menu(gs: &mut State, ctx: &mut BTerm) -> (ItemMenuResult, Opt
     );
 
     let mut equippable: Vec<Entity> = Vec::new();
-    let mut j = 0;
-    for (entity, _pack, name) in (&entities, &backpack, &names)
+    for (j, (entity, worn, _name)) in (&entities, &backpack, &names)
         .join()
         .filter(|item| item.1.owner == *player_entity)
+        .enumerate()
     {
+        let y = y + j as i32;
         ctx.set(17, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437('('));
         ctx.set(
             18,
@@ -522,10 +724,15 @@ pub fn remove_item_menu(gs: &mut State, ctx: &mut BTerm) -> (ItemMenuResult, Opt
         );
         ctx.set(19, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437(')'));
 
-        ctx.print(21, y, &name.name.to_string());
+        ctx.print_color(
+            21,
+            y,
+            item_display_color(&gs.ecs, entity),
+            RGB::named(BLACK),
+            item_display_name(&gs.ecs, entity),
+        );
+        ctx.print_color(47, y, RGB::named(GREY), RGB::named(BLACK), worn.slot.name());
         equippable.push(entity);
-        y += 1;
-        j += 1;
     }
 
     match ctx.key {
@@ -547,6 +754,253 @@ pub fn remove_item_menu(gs: &mut State, ctx: &mut BTerm) -> (ItemMenuResult, Opt
     }
 }
 
+pub fn cast_spell_menu(gs: &mut State, ctx: &mut BTerm) -> (ItemMenuResult, Option<Entity>) {
+    let player_entity = gs.ecs.fetch::<Entity>();
+    let names = gs.ecs.read_storage::<Name>();
+    let known_spells = gs.ecs.read_storage::<KnownSpell>();
+    let entities = gs.ecs.entities();
+    let mana = gs
+        .ecs
+        .read_storage::<Mana>()
+        .get(*player_entity)
+        .map_or(0, |m| m.mana);
+
+    let count = known_spells
+        .join()
+        .filter(|spell| spell.owner == *player_entity)
+        .count();
+
+    let y = (25 - (count / 2)) as i32;
+    ctx.draw_box(
+        15,
+        y - 2,
+        31,
+        count + 3,
+        RGB::named(WHITE),
+        RGB::named(BLACK),
+    );
+    ctx.print_color(
+        18,
+        y - 2,
+        RGB::named(YELLOW),
+        RGB::named(BLACK),
+        "Cast Which Spell?",
+    );
+    ctx.print_color(
+        18,
+        y + count as i32 + 1,
+        RGB::named(YELLOW),
+        RGB::named(BLACK),
+        "ESCAPE to cancel",
+    );
+    if count == 0 {
+        ctx.print_color(
+            21,
+            y,
+            RGB::named(GREY),
+            RGB::named(BLACK),
+            "You know no spells.",
+        );
+    }
+
+    let mut spells: Vec<Entity> = Vec::new();
+    for (j, (entity, spell, name)) in (&entities, &known_spells, &names)
+        .join()
+        .filter(|spell| spell.1.owner == *player_entity)
+        .enumerate()
+    {
+        let y = y + j as i32;
+        ctx.set(17, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437('('));
+        ctx.set(
+            18,
+            y,
+            RGB::named(YELLOW),
+            RGB::named(BLACK),
+            97 + j as FontCharType,
+        );
+        ctx.set(19, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437(')'));
+
+        let color = if spell.mana_cost <= mana {
+            RGB::named(WHITE)
+        } else {
+            RGB::named(GREY)
+        };
+        ctx.print_color(21, y, color, RGB::named(BLACK), &name.name);
+        ctx.print_color(
+            39,
+            y,
+            RGB::named(CYAN),
+            RGB::named(BLACK),
+            format!("{} mp", spell.mana_cost),
+        );
+        spells.push(entity);
+    }
+
+    match ctx.key {
+        None => (ItemMenuResult::NoResponse, None),
+        Some(key) => match key {
+            VirtualKeyCode::Escape => (ItemMenuResult::Cancel, None),
+            _ => {
+                let selection = letter_to_option(key);
+                if selection > -1 && selection < count as i32 {
+                    return (ItemMenuResult::Selected, Some(spells[selection as usize]));
+                }
+
+                (ItemMenuResult::NoResponse, None)
+            }
+        },
+    }
+}
+
+pub fn ally_orders_menu(ctx: &mut BTerm) -> (ItemMenuResult, Option<AllyOrder>) {
+    let orders = [
+        ('f', "Follow me", AllyOrder::Follow),
+        ('s', "Stay here", AllyOrder::Stay),
+        ('a', "Attack target", AllyOrder::Attack),
+    ];
+
+    let y = 23;
+    ctx.draw_box(15, y - 2, 31, 6, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_color(18, y - 2, RGB::named(YELLOW), RGB::named(BLACK), "Orders");
+    ctx.print_color(
+        18,
+        y + orders.len() as i32 + 1,
+        RGB::named(YELLOW),
+        RGB::named(BLACK),
+        "ESCAPE to cancel",
+    );
+
+    for (i, (key, label, _order)) in orders.iter().enumerate() {
+        let y = y + i as i32;
+        ctx.set(17, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437('('));
+        ctx.set(18, y, RGB::named(YELLOW), RGB::named(BLACK), to_cp437(*key));
+        ctx.set(19, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437(')'));
+        ctx.print(21, y, label);
+    }
+
+    match ctx.key {
+        None => (ItemMenuResult::NoResponse, None),
+        Some(key) => match key {
+            VirtualKeyCode::Escape => (ItemMenuResult::Cancel, None),
+            VirtualKeyCode::F => (ItemMenuResult::Selected, Some(AllyOrder::Follow)),
+            VirtualKeyCode::S => (ItemMenuResult::Selected, Some(AllyOrder::Stay)),
+            VirtualKeyCode::A => (ItemMenuResult::Selected, Some(AllyOrder::Attack)),
+            _ => (ItemMenuResult::NoResponse, None),
+        },
+    }
+}
+
+pub fn character_sheet(ecs: &World, ctx: &mut BTerm) -> ItemMenuResult {
+    let player_entity = ecs.fetch::<Entity>();
+    let combat_stats = ecs.read_storage::<CombatStats>();
+    let attributes = ecs.read_storage::<Attributes>();
+    let skills = ecs.read_storage::<Skills>();
+    let equipped = ecs.read_storage::<Equipped>();
+
+    ctx.draw_box(15, 12, 31, 20, RGB::named(WHITE), RGB::named(BLACK));
+    ctx.print_color(18, 12, RGB::named(YELLOW), RGB::named(BLACK), "Character");
+    ctx.print_color(
+        18,
+        32,
+        RGB::named(YELLOW),
+        RGB::named(BLACK),
+        "Press any key",
+    );
+
+    let mut y = 14;
+    if let Some(stats) = combat_stats.get(*player_entity) {
+        ctx.print(17, y, format!("Level: {}", stats.level));
+        ctx.print(
+            17,
+            y + 1,
+            format!("XP: {} / {}", stats.xp, xp_to_level_up(stats.level)),
+        );
+        ctx.print(17, y + 2, format!("HP: {} / {}", stats.hp, stats.max_hp));
+        y += 4;
+    }
+
+    if let Some(attrs) = attributes.get(*player_entity) {
+        for (label, attr) in [
+            ("Might", &attrs.might),
+            ("Fitness", &attrs.fitness),
+            ("Quickness", &attrs.quickness),
+            ("Intelligence", &attrs.intelligence),
+        ] {
+            let color = match attr.modifiers {
+                m if m > 0 => RGB::named(GREEN),
+                m if m < 0 => RGB::named(RED),
+                _ => RGB::named(WHITE),
+            };
+            ctx.print(17, y, label);
+            ctx.print_color(
+                31,
+                y,
+                color,
+                RGB::named(BLACK),
+                format!("{:>2} ({:+})", attr.value(), attr.bonus),
+            );
+            y += 1;
+        }
+        y += 1;
+    }
+
+    for (label, skill) in [
+        ("Melee", Skill::Melee),
+        ("Ranged", Skill::Ranged),
+        ("Defense", Skill::Defense),
+        ("Magic", Skill::Magic),
+    ] {
+        ctx.print(17, y, label);
+        ctx.print(
+            31,
+            y,
+            format!("{:+}", skill_level(skills.get(*player_entity), skill)),
+        );
+        y += 1;
+    }
+    y += 1;
+
+    let block = best_block(
+        *player_entity,
+        &ecs.entities(),
+        &ecs.read_storage::<BlockChance>(),
+        &equipped,
+    )
+    .map_or(0, |(_shield, percent)| percent);
+    for (label, value) in [
+        (
+            "Armor Class",
+            armor_class(
+                *player_entity,
+                &skills,
+                &attributes,
+                &ecs.read_storage::<DefenseBonus>(),
+                &equipped,
+            )
+            .to_string(),
+        ),
+        (
+            "Reduction",
+            damage_reduction(
+                *player_entity,
+                &ecs.read_storage::<DamageReduction>(),
+                &equipped,
+            )
+            .to_string(),
+        ),
+        ("Block", format!("{}%", block)),
+    ] {
+        ctx.print(17, y, label);
+        ctx.print(31, y, value);
+        y += 1;
+    }
+
+    match ctx.key {
+        None => ItemMenuResult::NoResponse,
+        Some(_) => ItemMenuResult::Cancel,
+    }
+}
+
 #[derive(PartialEq, Clone, Copy)]
 pub enum GameOverResult {
     NoSelection,
@@ -0,0 +1,127 @@
+use super::{Charges, Cursed, MagicItem, Name, Stack};
+use bracket_lib::prelude::*;
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+pub const POTIONS: &[&str] = &["Health Potion", "Haste Potion", "Regeneration Potion"];
+
+pub const SCROLLS: &[&str] = &[
+    "Fireball Scroll",
+    "Magic Missile Scroll",
+    "Confusion Scroll",
+    "Poison Cloud Scroll",
+    "Slow Scroll",
+    "Paralysis Scroll",
+    "Blindness Scroll",
+    "Scroll of Magic Mapping",
+    "Summon Ally Scroll",
+    "Remove Curse Scroll",
+    "Recharge Scroll",
+];
+
+const POTION_LOOKS: &[&str] = &[
+    "murky", "bubbling", "fizzy", "cloudy", "golden", "violet", "smoky", "glowing", "oily",
+    "viscous", "speckled", "pungent",
+];
+
+const SCROLL_SYLLABLES: &[&str] = &[
+    "xy", "zzy", "qua", "mor", "fen", "lo", "ka", "thu", "pre", "ny", "rok", "ash", "vel", "ix",
+];
+
+/// Which kinds of potion and scroll the player has learned, and what the
+/// rest look like until then. The disguises are rolled fresh for every run.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Identification {
+    pub identified: HashSet<String>,
+    pub disguises: HashMap<String, String>,
+}
+
+impl Identification {
+    pub fn new(rng: &mut RandomNumberGenerator) -> Self {
+        let mut disguises = HashMap::new();
+
+        let mut looks: Vec<&str> = POTION_LOOKS.to_vec();
+        for potion in POTIONS.iter() {
+            let look = looks.remove(rng.roll_dice(1, looks.len() as i32) as usize - 1);
+            disguises.insert(potion.to_string(), format!("{} potion", look));
+        }
+
+        let mut used: HashSet<String> = HashSet::new();
+        for scroll in SCROLLS.iter() {
+            let mut word = String::new();
+            while word.is_empty() || used.contains(&word) {
+                word = (0..rng.roll_dice(1, 2) + 1)
+                    .map(|_| {
+                        SCROLL_SYLLABLES
+                            [rng.roll_dice(1, SCROLL_SYLLABLES.len() as i32) as usize - 1]
+                    })
+                    .collect::<String>()
+                    .to_uppercase();
+            }
+            used.insert(word.clone());
+            disguises.insert(scroll.to_string(), format!("scroll of {}", word));
+        }
+
+        Identification {
+            identified: HashSet::new(),
+            disguises,
+        }
+    }
+
+    pub fn identify(&mut self, name: &str) {
+        self.identified.insert(name.to_string());
+    }
+
+    /// What the player knows `name` as: its true name once identified,
+    /// otherwise its disguise.
+    pub fn display_name(&self, name: &str) -> String {
+        match self.disguises.get(name) {
+            Some(disguise) if !self.identified.contains(name) => disguise.clone(),
+            _ => name.to_string(),
+        }
+    }
+}
+
+/// What the player knows an item as, without stack, charge or curse notes.
+pub fn item_base_name(ecs: &World, item: Entity) -> String {
+    match ecs.read_storage::<Name>().get(item) {
+        Some(name) => ecs.fetch::<Identification>().display_name(&name.name),
+        None => "Unnamed item".to_string(),
+    }
+}
+
+/// The one place that decides what an item is called on screen.
+pub fn item_display_name(ecs: &World, item: Entity) -> String {
+    let mut name = item_base_name(ecs, item);
+    if let Some(stack) = ecs.read_storage::<Stack>().get(item) {
+        name = format!("{} (x{})", name, stack.count);
+    }
+    if let Some(charges) = ecs.read_storage::<Charges>().get(item) {
+        if charges.uses > 0 {
+            name = format!("{} ({}/{})", name, charges.uses, charges.max_uses);
+        } else {
+            name = format!("{} (empty)", name);
+        }
+    }
+    match ecs.read_storage::<Cursed>().get(item) {
+        Some(curse) if curse.known => format!("{} (cursed)", name),
+        _ => name,
+    }
+}
+
+/// The color an item's name is drawn in: red once known to be cursed,
+/// otherwise the color of its rarity.
+pub fn item_display_color(ecs: &World, item: Entity) -> RGB {
+    if ecs
+        .read_storage::<Cursed>()
+        .get(item)
+        .is_some_and(|curse| curse.known)
+    {
+        return RGB::named(RED);
+    }
+    match ecs.read_storage::<MagicItem>().get(item) {
+        Some(magic) => magic.rarity.color(),
+        None => RGB::named(WHITE),
+    }
+}
@@ -3,6 +3,7 @@ use gamelog::GameLog;
 use gui::{MainMenuResult, MainMenuSelection};
 use specs::prelude::*;
 use specs::saveload::{SimpleMarker, SimpleMarkerAllocator};
+use std::collections::VecDeque;
 
 mod components;
 pub use components::*;
@@ -12,11 +13,18 @@ mod player;
 pub use player::*;
 mod systems;
 pub use systems::{
-    damage_system::*, hunger_system::*, inventory_system::*, map_indexing_system::*,
-    melee_combat_system::*, monster_ai_system::*, particle_system::*, trigger_system::*,
-    visibility_system::*,
+    ally_ai_system::*, attribute_system::*, damage_system::*, hunger_system::*,
+    inventory_system::*, item_ai_system::*, map_indexing_system::*, melee_combat_system::*,
+    monster_ai_system::*, noise_system::*, particle_system::*, ranged_combat_system::*,
+    regen_system::*, scent_system::*, spell_system::*, status_system::*, throw_system::*,
+    trigger_system::*, visibility_system::*,
 };
+mod effects;
+pub use effects::*;
+mod identification;
+pub use identification::Identification;
 mod gamelog;
+mod gamesystem;
 mod gui;
 mod map_builders;
 mod random_table;
@@ -60,7 +68,8 @@ fn main() -> BError {
     game_state.ecs.register::<Ranged>();
     game_state.ecs.register::<InflictsDamage>();
     game_state.ecs.register::<AreaOfEffect>();
-    game_state.ecs.register::<Confusion>();
+    game_state.ecs.register::<StatusEffects>();
+    game_state.ecs.register::<InflictsStatus>();
     game_state.ecs.register::<SimpleMarker<SerializeMe>>();
     game_state.ecs.register::<SerializationHelper>();
     game_state.ecs.register::<Equippable>();
@@ -76,6 +85,41 @@ fn main() -> BError {
     game_state.ecs.register::<EntryTrigger>();
     game_state.ecs.register::<EntityMoved>();
     game_state.ecs.register::<SingleActivation>();
+    game_state.ecs.register::<Alertness>();
+    game_state.ecs.register::<PackMember>();
+    game_state.ecs.register::<Ally>();
+    game_state.ecs.register::<AllyTarget>();
+    game_state.ecs.register::<SummonsAlly>();
+    game_state.ecs.register::<Tracker>();
+    game_state.ecs.register::<Attributes>();
+    game_state.ecs.register::<Skills>();
+    game_state.ecs.register::<AttributeBonus>();
+    game_state.ecs.register::<MeleeWeapon>();
+    game_state.ecs.register::<Resistances>();
+    game_state.ecs.register::<RangedWeapon>();
+    game_state.ecs.register::<Ammunition>();
+    game_state.ecs.register::<WantsToShoot>();
+    game_state.ecs.register::<Potion>();
+    game_state.ecs.register::<Throwable>();
+    game_state.ecs.register::<WantsToThrow>();
+    game_state.ecs.register::<TwoHanded>();
+    game_state.ecs.register::<DamageReduction>();
+    game_state.ecs.register::<HeavyArmor>();
+    game_state.ecs.register::<BlockChance>();
+    game_state.ecs.register::<Encumbrance>();
+    game_state.ecs.register::<Cursed>();
+    game_state.ecs.register::<RemovesCurse>();
+    game_state.ecs.register::<Charges>();
+    game_state.ecs.register::<Recharges>();
+    game_state.ecs.register::<MagicItem>();
+    game_state.ecs.register::<Stack>();
+    game_state.ecs.register::<Mana>();
+    game_state.ecs.register::<KnownSpell>();
+    game_state.ecs.register::<Spellbook>();
+    game_state.ecs.register::<WantsToCastSpell>();
+    game_state.ecs.register::<Teleports>();
+    game_state.ecs.register::<Alarm>();
+    game_state.ecs.register::<Trapdoor>();
 
     game_state
         .ecs
@@ -84,6 +128,9 @@ fn main() -> BError {
     game_state.ecs.insert(Map::new(1));
     game_state.ecs.insert(Point::new(0, 0));
     game_state.ecs.insert(RandomNumberGenerator::new());
+    let identification =
+        Identification::new(&mut game_state.ecs.write_resource::<RandomNumberGenerator>());
+    game_state.ecs.insert(identification);
 
     let player_entity = spawner::player(&mut game_state.ecs, 0, 0);
     game_state.ecs.insert(player_entity);
@@ -95,6 +142,8 @@ fn main() -> BError {
         entries: vec!["Welcome to the Dungeon".to_string()],
     });
     game_state.ecs.insert(ParticleBuilder::new());
+    game_state.ecs.insert(NoiseBuilder::new());
+    game_state.ecs.insert(EffectQueue::new());
     game_state.ecs.insert(rex_assets::RexAssets::new());
 
     game_state.generate_world_map(1);
@@ -152,7 +201,7 @@ impl GameState for State {
                     let mut data = (&positions, &renderables, !&hidden)
                         .join()
                         .collect::<Vec<_>>();
-                    data.sort_by(|&a, &b| b.1.render_order.cmp(&a.1.render_order));
+                    data.sort_by_key(|&a| std::cmp::Reverse(a.1.render_order));
                     for (pos, render, _hidden) in data.iter() {
                         let idx = map.xy_idx(pos.x, pos.y);
                         if map.visible_tiles[idx] {
@@ -181,6 +230,10 @@ impl GameState for State {
                     RunState::MagicMapReveal { .. } => {
                         new_run_state = RunState::MagicMapReveal { row: 0 }
                     }
+                    RunState::NextLevel => new_run_state = RunState::NextLevel,
+                    _ if player_has_extra_turn(&self.ecs) => {
+                        new_run_state = RunState::AwaitingInput
+                    }
                     _ => new_run_state = RunState::MonsterTurn,
                 }
             }
@@ -189,6 +242,24 @@ impl GameState for State {
                 self.ecs.maintain();
                 new_run_state = RunState::AwaitingInput;
             }
+            RunState::Resting { hunger } => match rest_interruption(&self.ecs, hunger) {
+                Some(reason) => {
+                    self.ecs
+                        .fetch_mut::<GameLog>()
+                        .entries
+                        .push(reason.to_string());
+                    new_run_state = RunState::AwaitingInput;
+                }
+                None => {
+                    // Every step of rest is a full turn, so the monsters get theirs too
+                    for turn in [RunState::PlayerTurn, RunState::MonsterTurn] {
+                        *self.ecs.write_resource::<RunState>() = turn;
+                        self.run_systems();
+                        self.ecs.maintain();
+                    }
+                    new_run_state = RunState::Resting { hunger };
+                }
+            },
             RunState::ShowInventory => {
                 let result = gui::show_inventory(self, ctx);
                 match result.0 {
@@ -225,7 +296,7 @@ impl GameState for State {
                     gui::ItemMenuResult::Cancel => new_run_state = RunState::AwaitingInput,
                     gui::ItemMenuResult::NoResponse => {}
                     gui::ItemMenuResult::Selected => {
-                        let item_entity = result.1.unwrap();
+                        let item_entity = spawner::split_stack(&mut self.ecs, result.1.unwrap());
                         let mut intent = self.ecs.write_storage::<WantsToDropItem>();
                         intent
                             .insert(
@@ -275,6 +346,137 @@ impl GameState for State {
                     }
                 }
             }
+            RunState::ShowAllyOrders => {
+                let result = gui::ally_orders_menu(ctx);
+                match result.0 {
+                    gui::ItemMenuResult::Cancel => new_run_state = RunState::AwaitingInput,
+                    gui::ItemMenuResult::NoResponse => {}
+                    gui::ItemMenuResult::Selected => match result.1 {
+                        Some(AllyOrder::Attack) => new_run_state = RunState::ShowAllyTarget,
+                        Some(order) => {
+                            self.give_ally_order(order, None);
+                            new_run_state = RunState::AwaitingInput;
+                        }
+                        None => new_run_state = RunState::AwaitingInput,
+                    },
+                }
+            }
+            RunState::ShowAllyTarget => {
+                let result = gui::ranged_target(self, ctx, 8);
+                match result.0 {
+                    gui::ItemMenuResult::Cancel => new_run_state = RunState::AwaitingInput,
+                    gui::ItemMenuResult::NoResponse => {}
+                    gui::ItemMenuResult::Selected => {
+                        let target = result.1.and_then(|point| self.monster_at(point));
+                        match target {
+                            Some(target) => self.give_ally_order(AllyOrder::Attack, Some(target)),
+                            None => self
+                                .ecs
+                                .fetch_mut::<GameLog>()
+                                .entries
+                                .push("There is nobody there to attack.".to_string()),
+                        }
+                        new_run_state = RunState::AwaitingInput;
+                    }
+                }
+            }
+            RunState::ShowRangedAttack { range } => {
+                let result = gui::ranged_target(self, ctx, range);
+                match result.0 {
+                    gui::ItemMenuResult::Cancel => new_run_state = RunState::AwaitingInput,
+                    gui::ItemMenuResult::NoResponse => {}
+                    gui::ItemMenuResult::Selected => {
+                        if let Some(ammo) = readied_ammo(&self.ecs) {
+                            let ammo = spawner::split_stack(&mut self.ecs, ammo);
+                            let mut intent = self.ecs.write_storage::<WantsToShoot>();
+                            intent
+                                .insert(
+                                    *self.ecs.fetch::<Entity>(),
+                                    WantsToShoot {
+                                        ammo,
+                                        target: result.1.unwrap(),
+                                    },
+                                )
+                                .expect("Unable to insert intent");
+                            new_run_state = RunState::PlayerTurn;
+                        } else {
+                            new_run_state = RunState::AwaitingInput;
+                        }
+                    }
+                }
+            }
+            RunState::ShowThrowItem => {
+                let result = gui::throw_item_menu(self, ctx);
+                match result.0 {
+                    gui::ItemMenuResult::Cancel => new_run_state = RunState::AwaitingInput,
+                    gui::ItemMenuResult::NoResponse => {}
+                    gui::ItemMenuResult::Selected => {
+                        new_run_state = RunState::ShowThrowTarget {
+                            item: result.1.unwrap(),
+                        };
+                    }
+                }
+            }
+            RunState::ShowThrowTarget { item } => {
+                let result = gui::ranged_target(self, ctx, gamesystem::THROW_RANGE);
+                match result.0 {
+                    gui::ItemMenuResult::Cancel => new_run_state = RunState::AwaitingInput,
+                    gui::ItemMenuResult::NoResponse => {}
+                    gui::ItemMenuResult::Selected => {
+                        let item = spawner::split_stack(&mut self.ecs, item);
+                        let mut intent = self.ecs.write_storage::<WantsToThrow>();
+                        intent
+                            .insert(
+                                *self.ecs.fetch::<Entity>(),
+                                WantsToThrow {
+                                    item,
+                                    target: result.1.unwrap(),
+                                },
+                            )
+                            .expect("Unable to insert intent");
+                        new_run_state = RunState::PlayerTurn;
+                    }
+                }
+            }
+            RunState::ShowCastSpell => {
+                let result = gui::cast_spell_menu(self, ctx);
+                match result.0 {
+                    gui::ItemMenuResult::Cancel => new_run_state = RunState::AwaitingInput,
+                    gui::ItemMenuResult::NoResponse => {}
+                    gui::ItemMenuResult::Selected => {
+                        let spell = result.1.unwrap();
+                        let range = self
+                            .ecs
+                            .read_storage::<Ranged>()
+                            .get(spell)
+                            .map(|r| r.range);
+                        if !self.can_cast(spell) {
+                            new_run_state = RunState::AwaitingInput;
+                        } else if let Some(range) = range {
+                            new_run_state = RunState::ShowSpellTarget { range, spell };
+                        } else {
+                            self.cast_spell(spell, None);
+                            new_run_state = RunState::PlayerTurn;
+                        }
+                    }
+                }
+            }
+            RunState::ShowSpellTarget { range, spell } => {
+                let result = gui::ranged_target(self, ctx, range);
+                match result.0 {
+                    gui::ItemMenuResult::Cancel => new_run_state = RunState::AwaitingInput,
+                    gui::ItemMenuResult::NoResponse => {}
+                    gui::ItemMenuResult::Selected => {
+                        self.cast_spell(spell, result.1);
+                        new_run_state = RunState::PlayerTurn;
+                    }
+                }
+            }
+            RunState::ShowCharacter => {
+                if gui::character_sheet(&self.ecs, ctx) == gui::ItemMenuResult::Cancel {
+                    new_run_state = RunState::AwaitingInput;
+                }
+            }
             RunState::MainMenu { .. } => {
                 let result = gui::main_menu(self, ctx);
                 match result {
@@ -356,26 +558,48 @@ impl State {
     fn run_systems(&mut self) {
         let mut vis = VisibilitySystem {};
         vis.run_now(&self.ecs);
+        let mut mob_items = MonsterItemAI {};
+        mob_items.run_now(&self.ecs);
         let mut mob = MonsterAI {};
         mob.run_now(&self.ecs);
+        let mut allies = AllyAI {};
+        allies.run_now(&self.ecs);
+        let mut scent = ScentSystem {};
+        scent.run_now(&self.ecs);
         let mut triggers = TriggerSystem {};
         triggers.run_now(&self.ecs);
         let mut map_index = MapIndexingSystem {};
         map_index.run_now(&self.ecs);
+        let mut attributes = AttributeSystem {};
+        attributes.run_now(&self.ecs);
         let mut melee = MeleeCombatSystem {};
         melee.run_now(&self.ecs);
+        let mut ranged = RangedCombatSystem {};
+        ranged.run_now(&self.ecs);
+        let mut throw = ThrowSystem {};
+        throw.run_now(&self.ecs);
+        effects::run_effects_queue(&mut self.ecs);
+        let mut noise = NoiseSystem {};
+        noise.run_now(&self.ecs);
         let mut damage = DamageSystem {};
         damage.run_now(&self.ecs);
         let mut pickup = ItemCollectionSystem {};
         pickup.run_now(&self.ecs);
         let mut items = ItemUseSystem {};
         items.run_now(&self.ecs);
+        let mut spells = SpellSystem {};
+        spells.run_now(&self.ecs);
         let mut drop_items = ItemDropSystem {};
         drop_items.run_now(&self.ecs);
         let mut item_remove = ItemRemoveSystem {};
         item_remove.run_now(&self.ecs);
         let mut hunger = HungerSystem {};
         hunger.run_now(&self.ecs);
+        let mut statuses = StatusSystem {};
+        statuses.run_now(&self.ecs);
+        let mut regen = RegenSystem {};
+        regen.run_now(&self.ecs);
+        effects::run_effects_queue(&mut self.ecs);
         let mut particles = ParticleSpawnSystem {};
         particles.run_now(&self.ecs);
 
@@ -388,6 +612,8 @@ impl State {
         let backpack = self.ecs.read_storage::<InBackpack>();
         let player_entity = self.ecs.fetch::<Entity>();
         let equipped = self.ecs.read_storage::<Equipped>();
+        let allies = self.ecs.read_storage::<Ally>();
+        let known_spells = self.ecs.read_storage::<KnownSpell>();
 
         let mut to_delete: Vec<Entity> = Vec::new();
         for entity in entities.join() {
@@ -398,6 +624,13 @@ impl State {
                 should_delete = false;
             }
 
+            // Allies come along unless they were told to stay put
+            if let Some(ally) = allies.get(entity) {
+                if ally.order != AllyOrder::Stay {
+                    should_delete = false;
+                }
+            }
+
             let bp = backpack.get(entity);
             if let Some(bp) = bp {
                 if bp.owner == *player_entity {
@@ -412,6 +645,13 @@ impl State {
                 }
             }
 
+            if known_spells
+                .get(entity)
+                .is_some_and(|spell| spell.owner == *player_entity)
+            {
+                should_delete = false;
+            }
+
             if should_delete {
                 to_delete.push(entity);
             }
@@ -447,6 +687,145 @@ impl State {
         }
     }
 
+    fn monster_at(&self, point: Point) -> Option<Entity> {
+        let map = self.ecs.fetch::<Map>();
+        let monsters = self.ecs.read_storage::<Monster>();
+        let idx = map.xy_idx(point.x, point.y);
+        map.tile_content[idx]
+            .iter()
+            .find(|e| monsters.get(**e).is_some())
+            .copied()
+    }
+
+    fn can_cast(&self, spell: Entity) -> bool {
+        let player_entity = self.ecs.fetch::<Entity>();
+        let cost = self
+            .ecs
+            .read_storage::<KnownSpell>()
+            .get(spell)
+            .map_or(0, |s| s.mana_cost);
+        let mana = self
+            .ecs
+            .read_storage::<Mana>()
+            .get(*player_entity)
+            .map_or(0, |m| m.mana);
+        if mana < cost {
+            let name = self
+                .ecs
+                .read_storage::<Name>()
+                .get(spell)
+                .unwrap()
+                .name
+                .clone();
+            self.ecs
+                .fetch_mut::<GameLog>()
+                .entries
+                .push(format!("You don't have enough mana to cast {}.", name));
+            return false;
+        }
+        true
+    }
+
+    fn cast_spell(&mut self, spell: Entity, target: Option<Point>) {
+        let mut intent = self.ecs.write_storage::<WantsToCastSpell>();
+        intent
+            .insert(
+                *self.ecs.fetch::<Entity>(),
+                WantsToCastSpell { spell, target },
+            )
+            .expect("Unable to insert intent");
+    }
+
+    fn give_ally_order(&mut self, order: AllyOrder, target: Option<Entity>) {
+        let entities = self.ecs.entities();
+        let mut allies = self.ecs.write_storage::<Ally>();
+        let mut ally_targets = self.ecs.write_storage::<AllyTarget>();
+        let mut count = 0;
+        for (entity, ally) in (&entities, &mut allies).join() {
+            ally.order = order;
+            ally_targets.remove(entity);
+            if let Some(target) = target {
+                ally_targets
+                    .insert(entity, AllyTarget { target })
+                    .expect("Unable to insert ally target.");
+            }
+            count += 1;
+        }
+
+        let mut gamelog = self.ecs.fetch_mut::<GameLog>();
+        if count == 0 {
+            gamelog
+                .entries
+                .push("You have nobody to give orders to.".to_string());
+        } else {
+            let message = match order {
+                AllyOrder::Follow => "You order your allies to follow you.",
+                AllyOrder::Stay => "You order your allies to hold their ground.",
+                AllyOrder::Attack => "You order your allies to attack!",
+            };
+            gamelog.entries.push(message.to_string());
+        }
+    }
+
+    /// Put allies who came down the stairs on free tiles around the player.
+    fn place_allies(&mut self, start: &Position) {
+        // Allies still hold their spots on the old level, so lift them off
+        // before indexing what was just spawned on the new one
+        {
+            let entities = self.ecs.entities();
+            let allies = self.ecs.read_storage::<Ally>();
+            let mut positions = self.ecs.write_storage::<Position>();
+            let following: Vec<Entity> = (&entities, &allies).join().map(|a| a.0).collect();
+            for ally in following {
+                positions.remove(ally);
+            }
+        }
+        let mut map_index = MapIndexingSystem {};
+        map_index.run_now(&self.ecs);
+
+        let mut stranded: Vec<Entity> = Vec::new();
+        {
+            let entities = self.ecs.entities();
+            let allies = self.ecs.read_storage::<Ally>();
+            let mut positions = self.ecs.write_storage::<Position>();
+            let mut viewsheds = self.ecs.write_storage::<Viewshed>();
+            let map = self.ecs.fetch::<Map>();
+
+            // Every free tile the player can walk to, nearest first
+            let mut free = reachable_tiles(&map, start)
+                .into_iter()
+                .filter(|idx| !map.blocked[*idx] && map.tile_content[*idx].is_empty());
+            for (entity, _ally) in (&entities, &allies).join() {
+                if let Some(idx) = free.next() {
+                    let x = idx as i32 % map.width;
+                    let y = idx as i32 / map.width;
+                    positions
+                        .insert(entity, Position { x, y })
+                        .expect("Unable to place ally.");
+                    if let Some(vs) = viewsheds.get_mut(entity) {
+                        vs.dirty = true;
+                    }
+                } else {
+                    stranded.push(entity);
+                }
+            }
+        }
+
+        // Nowhere to stand, so they stay behind like an ally told to wait
+        for ally in stranded {
+            let name = self
+                .ecs
+                .read_storage::<Name>()
+                .get(ally)
+                .map_or("ally".to_string(), |n| n.name.clone());
+            self.ecs.fetch_mut::<GameLog>().entries.push(format!(
+                "There is no room for your {}; it stays behind.",
+                name
+            ));
+            self.ecs.delete_entity(ally).expect("Unable to delete");
+        }
+    }
+
     fn game_over_cleanup(&mut self) {
         // Delete everything
         let mut to_delete = Vec::new();
@@ -457,6 +836,11 @@ impl State {
             self.ecs.delete_entity(*del).expect("Deletion failed");
         }
 
+        // A new run brings new disguises for potions and scrolls
+        let identification =
+            Identification::new(&mut self.ecs.write_resource::<RandomNumberGenerator>());
+        self.ecs.insert(identification);
+
         // Spawn a new player
         {
             let player_entity = spawner::player(&mut self.ecs, 0, 0);
@@ -483,6 +867,7 @@ impl State {
         }
 
         builder.spawn_entities(&mut self.ecs);
+        self.place_allies(&player_start);
 
         let mut player_position = self.ecs.write_resource::<Point>();
         *player_position = Point::new(player_start.x, player_start.y);
@@ -525,4 +910,51 @@ pub enum RunState {
         row: i32,
     },
     MapGeneration,
+    ShowAllyOrders,
+    ShowAllyTarget,
+    ShowCharacter,
+    ShowRangedAttack {
+        range: i32,
+    },
+    ShowThrowItem,
+    ShowThrowTarget {
+        item: Entity,
+    },
+    ShowCastSpell,
+    ShowSpellTarget {
+        range: i32,
+        spell: Entity,
+    },
+    Resting {
+        hunger: HungerState,
+    },
+}
+
+/// Tiles reachable on foot from `start`, in the order a flood fill reaches
+/// them. Monsters don't count as obstacles; the start tile isn't included.
+fn reachable_tiles(map: &Map, start: &Position) -> Vec<usize> {
+    let start_idx = map.xy_idx(start.x, start.y);
+    let mut seen = vec![false; map.tiles.len()];
+    seen[start_idx] = true;
+    let mut open = VecDeque::from([start_idx]);
+    let mut reached: Vec<usize> = Vec::new();
+    while let Some(idx) = open.pop_front() {
+        let x = idx as i32 % map.width;
+        let y = idx as i32 / map.width;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 1 || nx > map.width - 2 || ny < 1 || ny > map.height - 2 {
+                    continue;
+                }
+                let next = map.xy_idx(nx, ny);
+                if !seen[next] && map.tiles[next] != TileType::Wall {
+                    seen[next] = true;
+                    reached.push(next);
+                    open.push_back(next);
+                }
+            }
+        }
+    }
+    reached
 }
@@ -6,6 +6,7 @@ use std::collections::HashSet;
 pub const MAPWIDTH: usize = 80;
 pub const MAPHEIGHT: usize = 43;
 pub const MAPCOUNT: usize = MAPWIDTH * MAPHEIGHT;
+pub const SCENT_MAX: i32 = 100;
 
 #[derive(PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub enum TileType {
@@ -24,6 +25,7 @@ pub struct Map {
     pub blocked: Vec<bool>,
     pub depth: i32,
     pub bloodstains: HashSet<usize>,
+    pub scent: Vec<i32>,
 
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
@@ -66,6 +68,7 @@ impl Map {
             blocked: vec![false; MAPCOUNT],
             depth: new_depth,
             bloodstains: HashSet::new(),
+            scent: vec![0; MAPCOUNT],
             tile_content: vec![Vec::new(); MAPCOUNT],
         }
     }
@@ -139,7 +142,7 @@ pub fn draw_map(map: &Map, ctx: &mut BTerm) {
                     fg = RGB::from_f32(0.0, 0.5, 0.5);
                 }
                 TileType::Wall => {
-                    glyph = wall_glyph(&*map, x, y);
+                    glyph = wall_glyph(map, x, y);
                     fg = RGB::from_f32(0.0, 1.0, 0.0);
                 }
                 TileType::DownStairs => {
@@ -166,11 +169,11 @@ pub fn draw_map(map: &Map, ctx: &mut BTerm) {
 }
 
 fn wall_glyph(map: &Map, x: i32, y: i32) -> FontCharType {
-    if x < 1 || x > map.width - 2 as i32 {
+    if x < 1 || x > map.width - 2 {
         return 186;
     }
 
-    if y < 1 || y > map.height - 2 as i32 {
+    if y < 1 || y > map.height - 2 {
         return 205;
     }
 
@@ -84,7 +84,7 @@ impl BspMapBuilder {
         let Point { x, y } = self.rooms[0].center();
         self.starting_position = Position { x, y };
 
-        self.rooms.sort_by(|a, b| a.x1.cmp(&b.x1));
+        self.rooms.sort_by_key(|a| a.x1);
         for i in 0..self.rooms.len() - 1 {
             let room = self.rooms[i];
             let next_room = self.rooms[i + 1];
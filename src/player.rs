@@ -2,9 +2,30 @@ use crate::gamelog::GameLog;
 
 use super::*;
 use bracket_lib::prelude::{BTerm, Point, VirtualKeyCode};
-use std::cmp::{max, min};
 
-pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
+pub fn try_move_player(mut delta_x: i32, mut delta_y: i32, ecs: &mut World) {
+    // Confusion sends the player stumbling off in a random direction half the time
+    {
+        let player_entity = ecs.fetch::<Entity>();
+        let statuses = ecs.read_storage::<StatusEffects>();
+        if statuses
+            .get(*player_entity)
+            .is_some_and(|status| status.has(StatusKind::Confused))
+        {
+            let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+            if rng.roll_dice(1, 2) == 1 {
+                delta_x = rng.roll_dice(1, 3) - 2;
+                delta_y = rng.roll_dice(1, 3) - 2;
+                ecs.write_resource::<GameLog>()
+                    .entries
+                    .push("You stumble around in confusion.".to_string());
+            }
+        }
+    }
+    if delta_x == 0 && delta_y == 0 {
+        return;
+    }
+
     let mut positions = ecs.write_storage::<Position>();
     let players = ecs.read_storage::<Player>();
     let mut viewsheds = ecs.write_storage::<Viewshed>();
@@ -13,6 +34,19 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
     let entities = ecs.entities();
     let mut wants_to_melee = ecs.write_storage::<WantsToMelee>();
     let mut entity_moved = ecs.write_storage::<EntityMoved>();
+    let mut noise_builder = ecs.write_resource::<NoiseBuilder>();
+    let allies = ecs.read_storage::<Ally>();
+    let mut swap_with: Option<(Entity, Position)> = None;
+
+    // Every piece of heavy armor makes our footsteps that much louder
+    let footstep_noise = 3 + 2
+        * (
+            &ecs.read_storage::<HeavyArmor>(),
+            &ecs.read_storage::<Equipped>(),
+        )
+            .join()
+            .filter(|(_armor, equipped_by)| players.get(equipped_by.owner).is_some())
+            .count() as i32;
 
     for (pos, _, viewshed, entity) in (&mut positions, &players, &mut viewsheds, &entities).join() {
         if pos.x + delta_x < 1
@@ -24,6 +58,24 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
         }
         let destination_idx = map.xy_idx(pos.x + delta_x, pos.y + delta_y);
 
+        // Trade places with allies instead of attacking them
+        let ally = map.tile_content[destination_idx]
+            .iter()
+            .find(|e| allies.get(**e).is_some());
+        if let Some(ally) = ally {
+            swap_with = Some((*ally, pos.clone()));
+            pos.x += delta_x;
+            pos.y += delta_y;
+            entity_moved
+                .insert(entity, EntityMoved {})
+                .expect("Failed to insert marker.");
+            viewshed.dirty = true;
+            let mut ppos = ecs.write_resource::<Point>();
+            ppos.x = pos.x;
+            ppos.y = pos.y;
+            break;
+        }
+
         for potential_target in map.tile_content[destination_idx].iter() {
             let target = combat_stats.get(*potential_target);
             if let Some(_target) = target {
@@ -40,11 +92,12 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
         }
 
         if !map.blocked[destination_idx] {
-            pos.x = min(79, max(0, pos.x + delta_x));
-            pos.y = min(49, max(0, pos.y + delta_y));
+            pos.x = (pos.x + delta_x).clamp(0, 79);
+            pos.y = (pos.y + delta_y).clamp(0, 49);
             entity_moved
                 .insert(entity, EntityMoved {})
                 .expect("Failed to insert marker.");
+            noise_builder.request(pos.x, pos.y, footstep_noise);
 
             viewshed.dirty = true;
             let mut ppos = ecs.write_resource::<Point>();
@@ -52,9 +105,38 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
             ppos.y = pos.y;
         }
     }
+
+    if let Some((ally, old_pos)) = swap_with {
+        positions
+            .insert(ally, old_pos)
+            .expect("Failed to move ally.");
+        if let Some(vs) = viewsheds.get_mut(ally) {
+            vs.dirty = true;
+        }
+    }
+}
+
+/// Haste lets the player act again before the monsters get their turn.
+pub fn player_has_extra_turn(ecs: &World) -> bool {
+    let player_entity = ecs.fetch::<Entity>();
+    ecs.read_storage::<StatusEffects>()
+        .get(*player_entity)
+        .is_some_and(|status| status.extra_turn())
 }
 
 pub fn player_input(game_state: &mut State, ctx: &mut BTerm) -> RunState {
+    // Paralysed or slowed players watch the world go by
+    {
+        let player_entity = game_state.ecs.fetch::<Entity>();
+        let statuses = game_state.ecs.read_storage::<StatusEffects>();
+        if statuses
+            .get(*player_entity)
+            .is_some_and(|status| status.loses_turn())
+        {
+            return RunState::PlayerTurn;
+        }
+    }
+
     match ctx.key {
         None => return RunState::AwaitingInput,
         Some(key) => match key {
@@ -84,9 +166,10 @@ pub fn player_input(game_state: &mut State, ctx: &mut BTerm) -> RunState {
             VirtualKeyCode::Numpad1 => try_move_player(-1, 1, &mut game_state.ecs),
 
             // Skip Turn
-            VirtualKeyCode::Numpad5 | VirtualKeyCode::Space => {
-                return skip_turn(&mut game_state.ecs)
-            }
+            VirtualKeyCode::Numpad5 | VirtualKeyCode::Space => return RunState::PlayerTurn,
+
+            // Rest until healed or disturbed
+            VirtualKeyCode::W => return start_resting(&mut game_state.ecs),
 
             // Pickup item
             VirtualKeyCode::G => get_item(&mut game_state.ecs),
@@ -100,6 +183,27 @@ pub fn player_input(game_state: &mut State, ctx: &mut BTerm) -> RunState {
             // Unequip item
             VirtualKeyCode::R => return RunState::ShowRemoveItem,
 
+            // Give orders to allies
+            VirtualKeyCode::O => return RunState::ShowAllyOrders,
+
+            // Fire a ranged weapon
+            VirtualKeyCode::F => return ready_ranged_attack(&mut game_state.ecs),
+
+            // Throw an item
+            VirtualKeyCode::T => return RunState::ShowThrowItem,
+
+            // Cast a spell
+            VirtualKeyCode::Z => return RunState::ShowCastSpell,
+
+            // Search for hidden traps
+            VirtualKeyCode::S => search(&mut game_state.ecs),
+
+            // Disarm a trap
+            VirtualKeyCode::X => return disarm_trap(&mut game_state.ecs),
+
+            // Character sheet
+            VirtualKeyCode::C => return RunState::ShowCharacter,
+
             // Move downstairs
             VirtualKeyCode::Period => {
                 if try_next_level(&mut game_state.ecs) {
@@ -117,43 +221,207 @@ pub fn player_input(game_state: &mut State, ctx: &mut BTerm) -> RunState {
     RunState::PlayerTurn
 }
 
-fn skip_turn(ecs: &mut World) -> RunState {
+fn monster_in_view(ecs: &World) -> bool {
     let player_entity = ecs.fetch::<Entity>();
-    let viewshed_components = ecs.read_storage::<Viewshed>();
+    let viewsheds = ecs.read_storage::<Viewshed>();
     let monsters = ecs.read_storage::<Monster>();
+    let map = ecs.fetch::<Map>();
+
+    viewsheds.get(*player_entity).is_some_and(|viewshed| {
+        viewshed.visible_tiles.iter().any(|tile| {
+            map.tile_content[map.xy_idx(tile.x, tile.y)]
+                .iter()
+                .any(|e| monsters.get(*e).is_some())
+        })
+    })
+}
+
+fn start_resting(ecs: &mut World) -> RunState {
+    let player_entity = *ecs.fetch::<Entity>();
+    let (hunger, hungry) = ecs
+        .read_storage::<HungerClock>()
+        .get(player_entity)
+        .map_or((HungerState::Normal, false), |hc| (hc.state, hc.hungry()));
+    let reason = if monster_in_view(ecs) {
+        Some("You can't rest with enemies nearby.")
+    } else if hungry {
+        Some("You are too hungry to rest.")
+    } else {
+        rest_interruption(ecs, hunger)
+    };
+    if let Some(reason) = reason {
+        ecs.write_resource::<GameLog>()
+            .entries
+            .push(reason.to_string());
+        return RunState::AwaitingInput;
+    }
+
+    ecs.write_resource::<GameLog>()
+        .entries
+        .push("You settle down to rest.".to_string());
+    RunState::Resting { hunger }
+}
+
+/// Why the player should stop resting, if they should: a monster came into
+/// view, they are fully healed, or their hunger has moved on since they lay
+/// down.
+pub fn rest_interruption(ecs: &World, hunger: HungerState) -> Option<&'static str> {
+    let player_entity = *ecs.fetch::<Entity>();
+    if monster_in_view(ecs) {
+        return Some("You stop resting; something is coming!");
+    }
+    if ecs
+        .read_storage::<CombatStats>()
+        .get(player_entity)
+        .is_some_and(|stats| stats.hp >= stats.max_hp)
+    {
+        return Some("You feel fully rested.");
+    }
+    if ecs
+        .read_storage::<HungerClock>()
+        .get(player_entity)
+        .is_some_and(|hc| hc.state != hunger)
+    {
+        return Some("Your stomach interrupts your rest.");
+    }
+    None
+}
 
-    let worldmap_resource = ecs.fetch::<Map>();
-
-    let mut can_heal = true;
-    let viewshed = viewshed_components.get(*player_entity).unwrap();
-    for tile in viewshed.visible_tiles.iter() {
-        let idx = worldmap_resource.xy_idx(tile.x, tile.y);
-        for entity_id in worldmap_resource.tile_content[idx].iter() {
-            let mob = monsters.get(*entity_id);
-            match mob {
-                None => {}
-                Some(_) => can_heal = false,
+/// Looking carefully around finds hidden things far more often than just
+/// walking past them does.
+fn search(ecs: &mut World) {
+    let player_pos = *ecs.fetch::<Point>();
+    let player_entity = *ecs.fetch::<Entity>();
+    let intelligence_bonus = ecs
+        .read_storage::<Attributes>()
+        .get(player_entity)
+        .map_or(0, |attrs| attrs.intelligence.bonus);
+    let entities = ecs.entities();
+    let names = ecs.read_storage::<Name>();
+    let positions = ecs.read_storage::<Position>();
+    let mut hidden = ecs.write_storage::<Hidden>();
+    let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+    let mut log = ecs.write_resource::<GameLog>();
+
+    log.entries
+        .push("You search your surroundings.".to_string());
+    let nearby: Vec<Entity> = (&entities, &hidden, &positions)
+        .join()
+        .filter(|(_entity, _hidden, pos)| {
+            DistanceAlg::Pythagoras.distance2d(player_pos, Point::new(pos.x, pos.y)) <= 3.0
+        })
+        .map(|(entity, ..)| entity)
+        .collect();
+    for entity in nearby {
+        if rng.roll_dice(1, 20) + intelligence_bonus >= 10 {
+            if let Some(name) = names.get(entity) {
+                log.entries
+                    .push(format!("You've spotted a {}.", &name.name));
             }
+            hidden.remove(entity);
         }
     }
+}
 
-    let hunger_clocks = ecs.read_storage::<HungerClock>();
-    let hc = hunger_clocks.get(*player_entity);
-    if let Some(hc) = hc {
-        match hc.state {
-            HungerState::Hungry => can_heal = false,
-            HungerState::Starving => can_heal = false,
-            _ => {}
-        }
+/// Try to take apart a trap the player knows about, next to them or underfoot.
+/// Botching it badly sets the trap off.
+fn disarm_trap(ecs: &mut World) -> RunState {
+    let player_pos = *ecs.fetch::<Point>();
+    let player_entity = *ecs.fetch::<Entity>();
+    let trap = {
+        let entities = ecs.entities();
+        let triggers = ecs.read_storage::<EntryTrigger>();
+        let hidden = ecs.read_storage::<Hidden>();
+        let positions = ecs.read_storage::<Position>();
+        (&entities, &triggers, &positions, !&hidden)
+            .join()
+            .find(|(_entity, _trigger, pos, _hidden)| {
+                (pos.x - player_pos.x).abs() <= 1 && (pos.y - player_pos.y).abs() <= 1
+            })
+            .map(|(entity, ..)| entity)
+    };
+    let Some(trap) = trap else {
+        ecs.write_resource::<GameLog>()
+            .entries
+            .push("There is no trap here to disarm.".to_string());
+        return RunState::AwaitingInput;
+    };
+
+    let quickness_bonus = ecs
+        .read_storage::<Attributes>()
+        .get(player_entity)
+        .map_or(0, |attrs| attrs.quickness.bonus);
+    let roll = ecs
+        .write_resource::<RandomNumberGenerator>()
+        .roll_dice(1, 20)
+        + quickness_bonus;
+    let name = ecs.read_storage::<Name>().get(trap).unwrap().name.clone();
+    let mut log = ecs.write_resource::<GameLog>();
+    if roll >= 12 {
+        log.entries.push(format!("You disarm the {}.", name));
+        ecs.entities()
+            .delete(trap)
+            .expect("Failed to delete a trap.");
+    } else if roll >= 7 {
+        log.entries
+            .push(format!("You fail to disarm the {}.", name));
+    } else {
+        log.entries.push(format!("You fumble with the {}...", name));
+        ecs.write_resource::<EffectQueue>().add_effect(
+            None,
+            EffectType::TriggerFire { trigger: trap },
+            Targets::Single {
+                target: player_entity,
+            },
+        );
     }
+    RunState::PlayerTurn
+}
 
-    if can_heal {
-        let mut health_component = ecs.write_storage::<CombatStats>();
-        let player_hp = health_component.get_mut(*player_entity).unwrap();
-        player_hp.hp = i32::min(player_hp.hp + 1, player_hp.max_hp);
+/// The ammunition the player's equipped ranged weapon would loose next.
+pub fn readied_ammo(ecs: &World) -> Option<Entity> {
+    let player_entity = ecs.fetch::<Entity>();
+    let entities = ecs.entities();
+    let ranged_weapons = ecs.read_storage::<RangedWeapon>();
+    let equipped = ecs.read_storage::<Equipped>();
+    let ammunition = ecs.read_storage::<Ammunition>();
+    let backpack = ecs.read_storage::<InBackpack>();
+
+    let (weapon, _equipped_by) = (&ranged_weapons, &equipped)
+        .join()
+        .find(|(_weapon, equipped_by)| equipped_by.owner == *player_entity)?;
+    (&entities, &ammunition, &backpack)
+        .join()
+        .find(|(_ammo, ammo, carried)| {
+            carried.owner == *player_entity && ammo.ammo_type == weapon.ammo
+        })
+        .map(|(ammo, _ammo, _carried)| ammo)
+}
+
+fn ready_ranged_attack(ecs: &mut World) -> RunState {
+    let player_entity = ecs.fetch::<Entity>();
+    let ranged_weapons = ecs.read_storage::<RangedWeapon>();
+    let equipped = ecs.read_storage::<Equipped>();
+    let mut log = ecs.write_resource::<GameLog>();
+
+    let weapon = (&ranged_weapons, &equipped)
+        .join()
+        .find(|(_weapon, equipped_by)| equipped_by.owner == *player_entity)
+        .map(|(weapon, _equipped_by)| weapon);
+    let Some(weapon) = weapon else {
+        log.entries
+            .push("You have no ranged weapon ready.".to_string());
+        return RunState::AwaitingInput;
+    };
+
+    if readied_ammo(ecs).is_none() {
+        log.entries.push("You are out of ammunition.".to_string());
+        return RunState::AwaitingInput;
     }
 
-    RunState::PlayerTurn
+    RunState::ShowRangedAttack {
+        range: weapon.range,
+    }
 }
 
 fn get_item(ecs: &mut World) {
@@ -179,7 +447,7 @@ fn get_item(ecs: &mut World) {
                     *player_entity,
                     WantsToPickupItem {
                         collected_by: *player_entity,
-                        item: item,
+                        item,
                     },
                 )
                 .expect("Unable to insert WantToPickupItem component");
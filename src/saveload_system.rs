@@ -36,9 +36,13 @@ macro_rules! deserialize_individually {
 
 pub fn save_game(ecs: &mut World) {
     let mapcopy = ecs.get_mut::<super::map::Map>().unwrap().clone();
+    let identification = (*ecs.fetch::<Identification>()).clone();
     let savehelper = ecs
         .create_entity()
-        .with(SerializationHelper { map: mapcopy })
+        .with(SerializationHelper {
+            map: mapcopy,
+            identification,
+        })
         .marked::<SimpleMarker<SerializeMe>>()
         .build();
 
@@ -61,14 +65,14 @@ pub fn save_game(ecs: &mut World) {
             Name,
             BlocksTile,
             CombatStats,
-            SufferDamage,
             WantsToMelee,
             Item,
             Consumable,
             Ranged,
             InflictsDamage,
             AreaOfEffect,
-            Confusion,
+            StatusEffects,
+            InflictsStatus,
             ProvidesHealing,
             InBackpack,
             WantsToPickupItem,
@@ -85,7 +89,44 @@ pub fn save_game(ecs: &mut World) {
             Hidden,
             EntryTrigger,
             EntityMoved,
-            SingleActivation
+            SingleActivation,
+            Alertness,
+            PackMember,
+            Ally,
+            AllyTarget,
+            SummonsAlly,
+            Tracker,
+            Attributes,
+            Skills,
+            AttributeBonus,
+            MeleeWeapon,
+            Resistances,
+            RangedWeapon,
+            Ammunition,
+            WantsToShoot,
+            Potion,
+            Throwable,
+            WantsToThrow,
+            TwoHanded,
+            DamageReduction,
+            HeavyArmor,
+            BlockChance,
+            Encumbrance,
+            Cursed,
+            RemovesCurse,
+            Charges,
+            Recharges,
+            MagicItem,
+            MeleePowerBonus,
+            DefenseBonus,
+            Stack,
+            Mana,
+            KnownSpell,
+            Spellbook,
+            WantsToCastSpell,
+            Teleports,
+            Alarm,
+            Trapdoor
         );
     }
 
@@ -128,14 +169,14 @@ pub fn load_game(ecs: &mut World) {
             Name,
             BlocksTile,
             CombatStats,
-            SufferDamage,
             WantsToMelee,
             Item,
             Consumable,
             Ranged,
             InflictsDamage,
             AreaOfEffect,
-            Confusion,
+            StatusEffects,
+            InflictsStatus,
             ProvidesHealing,
             InBackpack,
             WantsToPickupItem,
@@ -152,7 +193,44 @@ pub fn load_game(ecs: &mut World) {
             Hidden,
             EntryTrigger,
             EntityMoved,
-            SingleActivation
+            SingleActivation,
+            Alertness,
+            PackMember,
+            Ally,
+            AllyTarget,
+            SummonsAlly,
+            Tracker,
+            Attributes,
+            Skills,
+            AttributeBonus,
+            MeleeWeapon,
+            Resistances,
+            RangedWeapon,
+            Ammunition,
+            WantsToShoot,
+            Potion,
+            Throwable,
+            WantsToThrow,
+            TwoHanded,
+            DamageReduction,
+            HeavyArmor,
+            BlockChance,
+            Encumbrance,
+            Cursed,
+            RemovesCurse,
+            Charges,
+            Recharges,
+            MagicItem,
+            MeleePowerBonus,
+            DefenseBonus,
+            Stack,
+            Mana,
+            KnownSpell,
+            Spellbook,
+            WantsToCastSpell,
+            Teleports,
+            Alarm,
+            Trapdoor
         );
     }
 
@@ -166,6 +244,7 @@ pub fn load_game(ecs: &mut World) {
             let mut worldmap = ecs.write_resource::<super::map::Map>();
             *worldmap = h.map.clone();
             worldmap.tile_content = vec![Vec::new(); super::map::MAPCOUNT];
+            *ecs.write_resource::<Identification>() = h.identification.clone();
             deleteme = Some(e);
         }
         for (e, _p, pos) in (&entities, &player, &position).join() {
@@ -28,34 +28,149 @@ pub fn player(ecs: &mut World, player_x: i32, player_y: i32) -> Entity {
             name: "Player".to_string(),
         })
         .with(CombatStats {
-            max_hp: 30,
-            hp: 30,
-            defense: 2,
-            power: 5,
+            max_hp: gamesystem::player_hp_at_level(11, 1),
+            hp: gamesystem::player_hp_at_level(11, 1),
+            level: 1,
+            xp: 0,
         })
+        .with(attributes(13, 11, 12, 11))
+        .with(skills(0, 0, 1, 1))
         .with(HungerClock {
             state: HungerState::WellFed,
-            duration: 20,
+            satiation: 1100,
+        })
+        .with(Mana {
+            mana: gamesystem::mana_at_level(11, 1),
+            max_mana: gamesystem::mana_at_level(11, 1),
+            regen_timer: 0,
         })
         .marked::<SimpleMarker<SerializeMe>>()
         .build()
 }
 
+fn attributes(might: i32, fitness: i32, quickness: i32, intelligence: i32) -> Attributes {
+    Attributes {
+        might: Attribute::new(might),
+        fitness: Attribute::new(fitness),
+        quickness: Attribute::new(quickness),
+        intelligence: Attribute::new(intelligence),
+    }
+}
+
+fn skills(melee: i32, ranged: i32, defense: i32, magic: i32) -> Skills {
+    let mut skills = HashMap::new();
+    skills.insert(Skill::Melee, melee);
+    skills.insert(Skill::Ranged, ranged);
+    skills.insert(Skill::Defense, defense);
+    skills.insert(Skill::Magic, magic);
+    Skills { skills }
+}
+
+fn resistances(resistances: &[(DamageType, Resistance)]) -> Resistances {
+    Resistances {
+        resistances: resistances.iter().cloned().collect(),
+    }
+}
+
 fn orc(ecs: &mut World, x: i32, y: i32) {
-    monster(ecs, x, y, to_cp437('o'), "Orc");
+    let orc = monster(ecs, x, y, to_cp437('o'), "Orc");
+    hunger_clock(ecs, orc);
+    ecs.write_storage::<Resistances>()
+        .insert(
+            orc,
+            resistances(&[(DamageType::Poison, Resistance::Resistant)]),
+        )
+        .expect("Unable to insert resistances.");
 }
 
 fn goblin(ecs: &mut World, x: i32, y: i32) {
     monster(ecs, x, y, to_cp437('g'), "Goblin");
 }
 
-fn monster<S: ToString>(ecs: &mut World, x: i32, y: i32, glyph: FontCharType, name: S) {
-    ecs.create_entity()
+fn hound(ecs: &mut World, x: i32, y: i32) {
+    let hound = monster(ecs, x, y, to_cp437('h'), "Hound");
+    hunger_clock(ecs, hound);
+    ecs.write_storage::<Tracker>()
+        .insert(hound, Tracker {})
+        .expect("Unable to insert tracker.");
+    ecs.write_storage::<Resistances>()
+        .insert(
+            hound,
+            resistances(&[(DamageType::Fire, Resistance::Vulnerable)]),
+        )
+        .expect("Unable to insert resistances.");
+}
+
+/// Some monsters get hungry too, each having last eaten at a different time.
+fn hunger_clock(ecs: &mut World, monster: Entity) {
+    let satiation = ecs
+        .write_resource::<RandomNumberGenerator>()
+        .roll_dice(1, 800)
+        + 100;
+    ecs.write_storage::<HungerClock>()
+        .insert(
+            monster,
+            HungerClock {
+                state: gamesystem::hunger_state(satiation),
+                satiation,
+            },
+        )
+        .expect("Unable to insert hunger clock.");
+}
+
+fn goblin_war_band(
+    ecs: &mut World,
+    x: i32,
+    y: i32,
+    room: &Rect,
+    spawn_points: &HashMap<usize, String>,
+) {
+    let pack = PackMember {
+        pack: (y as usize * MAPWIDTH) + x as usize,
+    };
+    let mut members = vec![(x, y)];
+    {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        let band_size = rng.roll_dice(1, 2) + 2;
+        let mut tries = 0;
+        while members.len() < band_size as usize && tries < 20 {
+            let mx = x + rng.roll_dice(1, 3) - 2;
+            let my = y + rng.roll_dice(1, 3) - 2;
+            let idx = (my as usize * MAPWIDTH) + mx as usize;
+            let inside = mx > room.x1 && mx <= room.x2 && my > room.y1 && my <= room.y2;
+            if inside && !spawn_points.contains_key(&idx) && !members.contains(&(mx, my)) {
+                members.push((mx, my));
+            } else {
+                tries += 1;
+            }
+        }
+    }
+
+    for (mx, my) in members {
+        let goblin = monster(ecs, mx, my, to_cp437('g'), "Goblin");
+        ecs.write_storage::<PackMember>()
+            .insert(goblin, pack.clone())
+            .expect("Unable to insert pack membership.");
+    }
+}
+
+fn monster<S: ToString>(ecs: &mut World, x: i32, y: i32, glyph: FontCharType, name: S) -> Entity {
+    let alert_state = {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        match rng.roll_dice(1, 3) {
+            1 => Some(AlertState::Asleep),
+            2 => Some(AlertState::Unaware),
+            _ => None,
+        }
+    };
+
+    let mut builder = ecs
+        .create_entity()
         .with(Position { x, y })
         .with(Renderable {
             fg: RGB::named(RED),
             bg: RGB::named(BLACK),
-            glyph: glyph,
+            glyph,
             render_order: 1,
         })
         .with(Viewshed {
@@ -69,11 +184,48 @@ fn monster<S: ToString>(ecs: &mut World, x: i32, y: i32, glyph: FontCharType, na
         })
         .with(BlocksTile {})
         .with(CombatStats {
-            max_hp: 16,
-            hp: 16,
-            defense: 1,
-            power: 4,
+            max_hp: gamesystem::npc_hp(10, 1),
+            hp: gamesystem::npc_hp(10, 1),
+            level: 1,
+            xp: 0,
+        })
+        .with(attributes(10, 10, 10, 10))
+        .with(skills(0, 0, 1, 0));
+    if let Some(state) = alert_state {
+        builder = builder.with(Alertness { state });
+    }
+    builder.marked::<SimpleMarker<SerializeMe>>().build()
+}
+
+pub fn summoned_warrior(lazy: &LazyUpdate, entities: &Entities, x: i32, y: i32) {
+    lazy.create_entity(entities)
+        .with(Position { x, y })
+        .with(Renderable {
+            fg: RGB::named(GREEN),
+            bg: RGB::named(BLACK),
+            glyph: to_cp437('w'),
+            render_order: 1,
+        })
+        .with(Viewshed {
+            visible_tiles: Vec::new(),
+            range: 8,
+            dirty: true,
+        })
+        .with(Ally {
+            order: AllyOrder::Follow,
+        })
+        .with(Name {
+            name: "Warrior".to_string(),
+        })
+        .with(BlocksTile {})
+        .with(CombatStats {
+            max_hp: gamesystem::npc_hp(14, 1),
+            hp: gamesystem::npc_hp(14, 1),
+            level: 1,
+            xp: 0,
         })
+        .with(attributes(10, 14, 10, 10))
+        .with(skills(0, 0, 1, 0))
         .marked::<SimpleMarker<SerializeMe>>()
         .build();
 }
@@ -93,8 +245,8 @@ pub fn spawn_room(ecs: &mut World, room: &Rect, map_depth: i32) {
                 let x = (room.x1 + rng.roll_dice(1, i32::abs(room.x2 - room.x1))) as usize;
                 let y = (room.y1 + rng.roll_dice(1, i32::abs(room.y2 - room.y1))) as usize;
                 let idx = (y * MAPWIDTH) + x;
-                if !spawn_points.contains_key(&idx) {
-                    spawn_points.insert(idx, spawn_table.roll(&mut rng));
+                if let std::collections::hash_map::Entry::Vacant(e) = spawn_points.entry(idx) {
+                    e.insert(spawn_table.roll(&mut rng));
                     added = true;
                 } else {
                     tries += 1;
@@ -107,40 +259,381 @@ pub fn spawn_room(ecs: &mut World, room: &Rect, map_depth: i32) {
         let x = (*spawn.0 % MAPWIDTH) as i32;
         let y = (*spawn.0 / MAPWIDTH) as i32;
 
+        // Equipment may roll up as a magic item
+        let equipment = match spawn.1.as_ref() {
+            "Dagger" => Some(dagger(ecs, x, y)),
+            "Shield" => Some(shield(ecs, x, y)),
+            "Longsword" => Some(longsword(ecs, x, y)),
+            "Tower Shield" => Some(tower_shield(ecs, x, y)),
+            "Greatsword" => Some(greatsword(ecs, x, y)),
+            "Leather Cap" => Some(light_armor(
+                ecs,
+                x,
+                y,
+                "Leather Cap",
+                EquipmentSlot::Head,
+                1,
+            )),
+            "Leather Armor" => Some(light_armor(
+                ecs,
+                x,
+                y,
+                "Leather Armor",
+                EquipmentSlot::Torso,
+                1,
+            )),
+            "Leather Leggings" => Some(light_armor(
+                ecs,
+                x,
+                y,
+                "Leather Leggings",
+                EquipmentSlot::Legs,
+                1,
+            )),
+            "Leather Boots" => Some(light_armor(
+                ecs,
+                x,
+                y,
+                "Leather Boots",
+                EquipmentSlot::Feet,
+                1,
+            )),
+            "Leather Gloves" => Some(light_armor(
+                ecs,
+                x,
+                y,
+                "Leather Gloves",
+                EquipmentSlot::Hands,
+                1,
+            )),
+            "Iron Helm" => Some(heavy_armor(ecs, x, y, "Iron Helm", EquipmentSlot::Head, 1)),
+            "Chain Mail" => Some(heavy_armor(
+                ecs,
+                x,
+                y,
+                "Chain Mail",
+                EquipmentSlot::Torso,
+                2,
+            )),
+            "Plate Armor" => Some(heavy_armor(
+                ecs,
+                x,
+                y,
+                "Plate Armor",
+                EquipmentSlot::Torso,
+                3,
+            )),
+            "Bow" => Some(bow(ecs, x, y)),
+            "Crossbow" => Some(crossbow(ecs, x, y)),
+            _ => None,
+        };
+        if let Some(item) = equipment {
+            roll_rarity(ecs, item, map_depth);
+            continue;
+        }
+
         match spawn.1.as_ref() {
             "Goblin" => goblin(ecs, x, y),
             "Orc" => orc(ecs, x, y),
+            "Hound" => hound(ecs, x, y),
+            "Goblin War Band" => goblin_war_band(ecs, x, y, room, &spawn_points),
             "Health Potion" => health_potion(ecs, x, y),
             "Fireball Scroll" => fireball_scroll(ecs, x, y),
             "Confusion Scroll" => confusion_scroll(ecs, x, y),
+            "Haste Potion" => haste_potion(ecs, x, y),
+            "Regeneration Potion" => regeneration_potion(ecs, x, y),
+            "Poison Cloud Scroll" => poison_cloud_scroll(ecs, x, y),
+            "Slow Scroll" => slow_scroll(ecs, x, y),
+            "Paralysis Scroll" => paralysis_scroll(ecs, x, y),
+            "Blindness Scroll" => blindness_scroll(ecs, x, y),
             "Magic Missile Scroll" => magic_missile_scroll(ecs, x, y),
-            "Dagger" => dagger(ecs, x, y),
-            "Shield" => shield(ecs, x, y),
-            "Longsword" => longsword(ecs, x, y),
-            "Tower Shield" => tower_shield(ecs, x, y),
+            "Amulet of Vitality" => jewellery(
+                ecs,
+                x,
+                y,
+                "Amulet of Vitality",
+                EquipmentSlot::Amulet,
+                AttributeBonus {
+                    fitness: 1,
+                    ..Default::default()
+                },
+            ),
+            "Ring of Might" => jewellery(
+                ecs,
+                x,
+                y,
+                "Ring of Might",
+                EquipmentSlot::Ring1,
+                AttributeBonus {
+                    might: 1,
+                    ..Default::default()
+                },
+            ),
+            "Cursed Ring" => cursed_ring(ecs, x, y),
+            "Cursed Longsword" => cursed_longsword(ecs, x, y),
+            "Remove Curse Scroll" => remove_curse_scroll(ecs, x, y),
+            "Recharge Scroll" => recharge_scroll(ecs, x, y),
+            "Wand of Magic Missile" => magic_missile_wand(ecs, x, y),
+            "Wand of Fire" => fire_wand(ecs, x, y),
+            "Staff of Confusion" => confusion_staff(ecs, x, y),
+            "Ring of Quickness" => jewellery(
+                ecs,
+                x,
+                y,
+                "Ring of Quickness",
+                EquipmentSlot::Ring1,
+                AttributeBonus {
+                    quickness: 1,
+                    ..Default::default()
+                },
+            ),
+            "Arrows" => ammunition(ecs, x, y, AmmoType::Arrow),
+            "Bolts" => ammunition(ecs, x, y, AmmoType::Bolt),
             "Magic Mapping Scroll" => magic_mapping_scroll(ecs, x, y),
-            "Rations" => rations(ecs, x, y),
+            "Rations" => food(ecs, x, y, "Rations", RGB::named(GREEN), 800, 0.5),
+            "Apple" => food(ecs, x, y, "Apple", RGB::named(RED), 200, 0.3),
+            "Dried Meat" => food(ecs, x, y, "Dried Meat", RGB::named(SANDYBROWN), 450, 0.5),
             "Bear Trap" => bear_trap(ecs, x, y),
+            "Teleport Trap" => teleport_trap(ecs, x, y),
+            "Alarm Trap" => alarm_trap(ecs, x, y),
+            "Confusion Gas Trap" => confusion_gas_trap(ecs, x, y),
+            "Trapdoor" => trapdoor(ecs, x, y),
+            "Summon Ally Scroll" => summon_ally_scroll(ecs, x, y),
+            "Spellbook of Magic Missile" => spellbook(ecs, x, y, "Magic Missile"),
+            "Spellbook of Fireball" => spellbook(ecs, x, y, "Fireball"),
+            "Spellbook of Confusion" => spellbook(ecs, x, y, "Confusion"),
+            "Spellbook of Healing" => spellbook(ecs, x, y, "Heal"),
             _ => {}
         }
     }
 }
 
+/// Takes a single item off the top of a stack, leaving the rest where they
+/// are. Anything that isn't stacked is returned as-is.
+pub fn split_stack(ecs: &mut World, item: Entity) -> Entity {
+    let count = ecs.read_storage::<Stack>().get(item).map_or(1, |s| s.count);
+    if count < 2 {
+        return item;
+    }
+    if count == 2 {
+        ecs.write_storage::<Stack>().remove(item);
+    } else {
+        ecs.write_storage::<Stack>()
+            .insert(item, Stack { count: count - 1 })
+            .expect("Unable to shrink stack");
+    }
+
+    let single = ecs
+        .create_entity()
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+    copy_component::<InBackpack>(ecs, item, single);
+    copy_component::<Name>(ecs, item, single);
+    copy_component::<Renderable>(ecs, item, single);
+    copy_component::<Item>(ecs, item, single);
+    copy_component::<Consumable>(ecs, item, single);
+    copy_component::<Potion>(ecs, item, single);
+    copy_component::<Throwable>(ecs, item, single);
+    copy_component::<ProvidesHealing>(ecs, item, single);
+    copy_component::<ProvidesFood>(ecs, item, single);
+    copy_component::<InflictsDamage>(ecs, item, single);
+    copy_component::<InflictsStatus>(ecs, item, single);
+    copy_component::<Ranged>(ecs, item, single);
+    copy_component::<AreaOfEffect>(ecs, item, single);
+    copy_component::<MagicMapper>(ecs, item, single);
+    copy_component::<SummonsAlly>(ecs, item, single);
+    copy_component::<RemovesCurse>(ecs, item, single);
+    copy_component::<Recharges>(ecs, item, single);
+    copy_component::<Spellbook>(ecs, item, single);
+    copy_component::<Ammunition>(ecs, item, single);
+    single
+}
+
+fn copy_component<T: Component + Clone>(ecs: &World, from: Entity, to: Entity) {
+    let mut storage = ecs.write_storage::<T>();
+    if let Some(component) = storage.get(from).cloned() {
+        storage
+            .insert(to, component)
+            .expect("Unable to copy component");
+    }
+}
+
+/// Most equipment is plain, but some rolls up as a magic item with one affix,
+/// or a rare one with both a "+N" prefix and an "of ..." suffix.
+fn roll_rarity(ecs: &mut World, item: Entity, map_depth: i32) {
+    let (rarity, prefix, suffix) = {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        let roll = rng.roll_dice(1, 100);
+        let rarity = if roll <= 3 + map_depth {
+            Rarity::Rare
+        } else if roll <= 15 + map_depth * 2 {
+            Rarity::Magic
+        } else {
+            return;
+        };
+        let has_prefix = rarity == Rarity::Rare || rng.roll_dice(1, 2) == 1;
+        let has_suffix = rarity == Rarity::Rare || !has_prefix;
+        let prefix = has_prefix.then(|| rng.roll_dice(1, 2));
+        let suffix = has_suffix.then(|| rng.roll_dice(1, 3));
+        (rarity, prefix, suffix)
+    };
+    let is_weapon = ecs.read_storage::<MeleeWeapon>().get(item).is_some()
+        || ecs.read_storage::<RangedWeapon>().get(item).is_some();
+
+    let mut name = ecs.read_storage::<Name>().get(item).unwrap().name.clone();
+    if let Some(plus) = prefix {
+        name = format!("+{} {}", plus, name);
+        // The bonus goes on the weapon itself, so it only counts when that
+        // weapon strikes
+        if let Some(weapon) = ecs.write_storage::<RangedWeapon>().get_mut(item) {
+            weapon.damage_bonus += plus;
+        }
+        if let Some(weapon) = ecs.write_storage::<MeleeWeapon>().get_mut(item) {
+            weapon.damage_bonus += plus;
+        }
+        if !is_weapon {
+            add_defense_bonus(ecs, item, plus);
+        }
+    }
+    match suffix {
+        Some(1) if is_weapon => {
+            name = format!("{} of Fire", name);
+            ecs.write_storage::<InflictsDamage>()
+                .insert(
+                    item,
+                    InflictsDamage {
+                        damage: 1 + map_depth / 2,
+                        damage_type: DamageType::Fire,
+                    },
+                )
+                .expect("Unable to insert affix");
+        }
+        Some(1) => {
+            name = format!("{} of Fire Resistance", name);
+            let mut resistances = ecs.write_storage::<Resistances>();
+            match resistances.get_mut(item) {
+                Some(r) => {
+                    r.resistances
+                        .insert(DamageType::Fire, Resistance::Resistant);
+                }
+                None => {
+                    resistances
+                        .insert(
+                            item,
+                            self::resistances(&[(DamageType::Fire, Resistance::Resistant)]),
+                        )
+                        .expect("Unable to insert affix");
+                }
+            }
+        }
+        Some(2) => {
+            name = format!("{} of Protection", name);
+            add_defense_bonus(ecs, item, 1);
+        }
+        Some(_) => {
+            name = format!("{} of Might", name);
+            let mut bonuses = ecs.write_storage::<AttributeBonus>();
+            match bonuses.get_mut(item) {
+                Some(bonus) => bonus.might += 1,
+                None => {
+                    bonuses
+                        .insert(
+                            item,
+                            AttributeBonus {
+                                might: 1,
+                                ..Default::default()
+                            },
+                        )
+                        .expect("Unable to insert affix");
+                }
+            }
+        }
+        None => {}
+    }
+
+    ecs.write_storage::<Name>()
+        .insert(item, Name { name })
+        .expect("Unable to rename magic item");
+    if let Some(details) = ecs.write_storage::<Item>().get_mut(item) {
+        details.value *= match rarity {
+            Rarity::Magic => 2,
+            Rarity::Rare => 4,
+        };
+    }
+    ecs.write_storage::<MagicItem>()
+        .insert(item, MagicItem { rarity })
+        .expect("Unable to insert rarity");
+}
+
+fn add_defense_bonus(ecs: &mut World, item: Entity, defense: i32) {
+    let mut bonuses = ecs.write_storage::<DefenseBonus>();
+    match bonuses.get_mut(item) {
+        Some(bonus) => bonus.defense += defense,
+        None => {
+            bonuses
+                .insert(item, DefenseBonus { defense })
+                .expect("Unable to insert affix");
+        }
+    }
+}
+
 fn room_table(map_depth: i32) -> random_table::RandomTable {
     random_table::RandomTable::new()
         .add("Goblin", 10)
         .add("Orc", 1 + map_depth)
+        .add("Goblin War Band", map_depth - 1)
+        .add("Hound", map_depth)
         .add("Health Potion", 7)
         .add("Fireball Scroll", 2 + map_depth)
         .add("Confusion Scroll", 2 + map_depth)
+        .add("Haste Potion", 2)
+        .add("Regeneration Potion", 3)
+        .add("Poison Cloud Scroll", 1 + map_depth)
+        .add("Slow Scroll", 2)
+        .add("Paralysis Scroll", map_depth - 1)
+        .add("Blindness Scroll", 2)
         .add("Magic Missile Scroll", 4)
         .add("Dagger", 3)
         .add("Shield", 3)
         .add("Longsword", map_depth - 1)
         .add("Tower Shield", map_depth - 1)
+        .add("Greatsword", map_depth - 1)
+        .add("Leather Cap", 2)
+        .add("Leather Armor", 2)
+        .add("Iron Helm", map_depth - 1)
+        .add("Chain Mail", map_depth - 1)
+        .add("Plate Armor", map_depth - 3)
+        .add("Leather Leggings", 2)
+        .add("Leather Boots", 2)
+        .add("Leather Gloves", 2)
+        .add("Amulet of Vitality", map_depth - 1)
+        .add("Ring of Might", map_depth - 1)
+        .add("Ring of Quickness", map_depth - 1)
+        .add("Cursed Ring", map_depth - 1)
+        .add("Cursed Longsword", map_depth - 1)
+        .add("Remove Curse Scroll", 2)
+        .add("Recharge Scroll", 1 + map_depth / 2)
+        .add("Wand of Magic Missile", 2)
+        .add("Wand of Fire", map_depth - 1)
+        .add("Staff of Confusion", map_depth - 1)
+        .add("Bow", 2)
+        .add("Crossbow", map_depth - 1)
+        .add("Arrows", 3)
+        .add("Bolts", map_depth - 1)
         .add("Magic Mapping Scroll", 2)
-        .add("Rations", 10)
+        .add("Rations", 6)
+        .add("Apple", 5)
+        .add("Dried Meat", 4)
         .add("Bear Trap", 2)
+        .add("Teleport Trap", map_depth / 2)
+        .add("Alarm Trap", 1 + map_depth / 2)
+        .add("Confusion Gas Trap", map_depth - 1)
+        .add("Trapdoor", map_depth - 1)
+        .add("Summon Ally Scroll", 2)
+        .add("Spellbook of Magic Missile", 2)
+        .add("Spellbook of Healing", 1 + map_depth / 2)
+        .add("Spellbook of Confusion", map_depth - 1)
+        .add("Spellbook of Fireball", map_depth - 2)
 }
 
 fn health_potion(ecs: &mut World, x: i32, y: i32) {
@@ -155,8 +648,12 @@ fn health_potion(ecs: &mut World, x: i32, y: i32) {
         .with(Name {
             name: "Health Potion".to_string(),
         })
-        .with(Item {})
+        .with(Item {
+            weight: 0.5,
+            value: 50,
+        })
         .with(Consumable {})
+        .with(Potion {})
         .with(ProvidesHealing { heal_amount: 8 })
         .marked::<SimpleMarker<SerializeMe>>()
         .build();
@@ -174,10 +671,16 @@ fn fireball_scroll(ecs: &mut World, x: i32, y: i32) {
         .with(Name {
             name: "Fireball Scroll".to_string(),
         })
-        .with(Item {})
+        .with(Item {
+            weight: 0.5,
+            value: 100,
+        })
         .with(Consumable {})
         .with(Ranged { range: 6 })
-        .with(InflictsDamage { damage: 20 })
+        .with(InflictsDamage {
+            damage: 20,
+            damage_type: DamageType::Fire,
+        })
         .with(AreaOfEffect { radius: 3 })
         .marked::<SimpleMarker<SerializeMe>>()
         .build();
@@ -195,10 +698,16 @@ fn magic_missile_scroll(ecs: &mut World, x: i32, y: i32) {
         .with(Name {
             name: "Magic Missile Scroll".to_string(),
         })
-        .with(Item {})
+        .with(Item {
+            weight: 0.5,
+            value: 50,
+        })
         .with(Consumable {})
         .with(Ranged { range: 6 })
-        .with(InflictsDamage { damage: 8 })
+        .with(InflictsDamage {
+            damage: 8,
+            damage_type: DamageType::Physical,
+        })
         .marked::<SimpleMarker<SerializeMe>>()
         .build();
 }
@@ -215,15 +724,185 @@ fn confusion_scroll(ecs: &mut World, x: i32, y: i32) {
         .with(Name {
             name: "Confusion Scroll".to_string(),
         })
-        .with(Item {})
+        .with(Item {
+            weight: 0.5,
+            value: 60,
+        })
         .with(Consumable {})
         .with(Ranged { range: 4 })
-        .with(Confusion { turns: 4 })
+        .with(InflictsStatus {
+            kind: StatusKind::Confused,
+            turns: 4,
+            potency: 0,
+        })
         .marked::<SimpleMarker<SerializeMe>>()
         .build();
 }
 
-fn dagger(ecs: &mut World, x: i32, y: i32) {
+fn haste_potion(ecs: &mut World, x: i32, y: i32) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437('i'),
+            fg: RGB::named(YELLOW),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Haste Potion".to_string(),
+        })
+        .with(Item {
+            weight: 0.5,
+            value: 80,
+        })
+        .with(Consumable {})
+        .with(Potion {})
+        .with(InflictsStatus {
+            kind: StatusKind::Hasted,
+            turns: 10,
+            potency: 0,
+        })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn regeneration_potion(ecs: &mut World, x: i32, y: i32) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437('i'),
+            fg: RGB::named(GREEN),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Regeneration Potion".to_string(),
+        })
+        .with(Item {
+            weight: 0.5,
+            value: 80,
+        })
+        .with(Consumable {})
+        .with(Potion {})
+        .with(InflictsStatus {
+            kind: StatusKind::Regenerating,
+            turns: 10,
+            potency: 1,
+        })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn poison_cloud_scroll(ecs: &mut World, x: i32, y: i32) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437(')'),
+            fg: RGB::named(GREEN),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Poison Cloud Scroll".to_string(),
+        })
+        .with(Item {
+            weight: 0.5,
+            value: 90,
+        })
+        .with(Consumable {})
+        .with(Ranged { range: 6 })
+        .with(AreaOfEffect { radius: 2 })
+        .with(InflictsStatus {
+            kind: StatusKind::Poisoned,
+            turns: 5,
+            potency: 1,
+        })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn slow_scroll(ecs: &mut World, x: i32, y: i32) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437(')'),
+            fg: RGB::named(BLUE),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Slow Scroll".to_string(),
+        })
+        .with(Item {
+            weight: 0.5,
+            value: 60,
+        })
+        .with(Consumable {})
+        .with(Ranged { range: 6 })
+        .with(InflictsStatus {
+            kind: StatusKind::Slowed,
+            turns: 8,
+            potency: 0,
+        })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn paralysis_scroll(ecs: &mut World, x: i32, y: i32) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437(')'),
+            fg: RGB::named(ORANGE),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Paralysis Scroll".to_string(),
+        })
+        .with(Item {
+            weight: 0.5,
+            value: 120,
+        })
+        .with(Consumable {})
+        .with(Ranged { range: 6 })
+        .with(InflictsStatus {
+            kind: StatusKind::Paralyzed,
+            turns: 3,
+            potency: 0,
+        })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn blindness_scroll(ecs: &mut World, x: i32, y: i32) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437(')'),
+            fg: RGB::named(GREY),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Blindness Scroll".to_string(),
+        })
+        .with(Item {
+            weight: 0.5,
+            value: 60,
+        })
+        .with(Consumable {})
+        .with(Ranged { range: 6 })
+        .with(InflictsStatus {
+            kind: StatusKind::Blinded,
+            turns: 6,
+            potency: 0,
+        })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn dagger(ecs: &mut World, x: i32, y: i32) -> Entity {
     ecs.create_entity()
         .with(Position { x, y })
         .with(Renderable {
@@ -235,16 +914,20 @@ fn dagger(ecs: &mut World, x: i32, y: i32) {
         .with(Name {
             name: "Dagger".to_string(),
         })
-        .with(Item {})
+        .with(Item {
+            weight: 1.0,
+            value: 10,
+        })
         .with(Equippable {
             slot: EquipmentSlot::Melee,
         })
-        .with(MeleePowerBonus { power: 2 })
+        .with(gamesystem::melee_weapon(1, "1d4+1"))
+        .with(Throwable {})
         .marked::<SimpleMarker<SerializeMe>>()
-        .build();
+        .build()
 }
 
-fn longsword(ecs: &mut World, x: i32, y: i32) {
+fn longsword(ecs: &mut World, x: i32, y: i32) -> Entity {
     ecs.create_entity()
         .with(Position { x, y })
         .with(Renderable {
@@ -256,16 +939,227 @@ fn longsword(ecs: &mut World, x: i32, y: i32) {
         .with(Name {
             name: "Longsword".to_string(),
         })
-        .with(Item {})
+        .with(Item {
+            weight: 3.0,
+            value: 30,
+        })
+        .with(Equippable {
+            slot: EquipmentSlot::Melee,
+        })
+        .with(gamesystem::melee_weapon(0, "1d8+1"))
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build()
+}
+
+fn greatsword(ecs: &mut World, x: i32, y: i32) -> Entity {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437('/'),
+            fg: RGB::named(LIGHT_BLUE),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Greatsword".to_string(),
+        })
+        .with(Item {
+            weight: 6.0,
+            value: 60,
+        })
         .with(Equippable {
             slot: EquipmentSlot::Melee,
         })
-        .with(MeleePowerBonus { power: 4 })
+        .with(TwoHanded {})
+        .with(gamesystem::melee_weapon(0, "2d6+1"))
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build()
+}
+
+/// Rough weight of a piece of leather armor for each part of the body.
+fn slot_weight(slot: EquipmentSlot) -> f32 {
+    match slot {
+        EquipmentSlot::Torso => 10.0,
+        EquipmentSlot::Head | EquipmentSlot::Legs => 3.0,
+        _ => 2.0,
+    }
+}
+
+/// Light armor makes the wearer harder to hit.
+fn light_armor(
+    ecs: &mut World,
+    x: i32,
+    y: i32,
+    name: &str,
+    slot: EquipmentSlot,
+    defense: i32,
+) -> Entity {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437('['),
+            fg: RGB::named(SANDYBROWN),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: name.to_string(),
+        })
+        .with(Item {
+            weight: slot_weight(slot),
+            value: 10 * defense + 5,
+        })
+        .with(Equippable { slot })
+        .with(DefenseBonus { defense })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build()
+}
+
+/// Heavy armor soaks up blows, but slows the wearer and makes them noisy.
+fn heavy_armor(
+    ecs: &mut World,
+    x: i32,
+    y: i32,
+    name: &str,
+    slot: EquipmentSlot,
+    reduction: i32,
+) -> Entity {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437('['),
+            fg: RGB::named(LIGHT_SLATE),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: name.to_string(),
+        })
+        .with(Item {
+            weight: slot_weight(slot) * (1 + reduction) as f32,
+            value: 25 * reduction,
+        })
+        .with(Equippable { slot })
+        .with(DamageReduction { amount: reduction })
+        .with(HeavyArmor {})
+        .with(AttributeBonus {
+            quickness: -1,
+            ..Default::default()
+        })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build()
+}
+
+fn jewellery(
+    ecs: &mut World,
+    x: i32,
+    y: i32,
+    name: &str,
+    slot: EquipmentSlot,
+    bonus: AttributeBonus,
+) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437(if slot == EquipmentSlot::Amulet {
+                '"'
+            } else {
+                '='
+            }),
+            fg: RGB::named(GOLD),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: name.to_string(),
+        })
+        .with(Item {
+            weight: 0.1,
+            value: 100,
+        })
+        .with(Equippable { slot })
+        .with(bonus)
         .marked::<SimpleMarker<SerializeMe>>()
         .build();
 }
 
-fn shield(ecs: &mut World, x: i32, y: i32) {
+fn bow(ecs: &mut World, x: i32, y: i32) -> Entity {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437(')'),
+            fg: RGB::named(BURLYWOOD),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Bow".to_string(),
+        })
+        .with(Item {
+            weight: 2.0,
+            value: 30,
+        })
+        .with(Equippable {
+            slot: EquipmentSlot::Ranged,
+        })
+        .with(gamesystem::ranged_weapon(6, 0, "1d6", AmmoType::Arrow))
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build()
+}
+
+fn crossbow(ecs: &mut World, x: i32, y: i32) -> Entity {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437(')'),
+            fg: RGB::named(GREY),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Crossbow".to_string(),
+        })
+        .with(Item {
+            weight: 5.0,
+            value: 50,
+        })
+        .with(Equippable {
+            slot: EquipmentSlot::Ranged,
+        })
+        .with(gamesystem::ranged_weapon(8, 1, "1d8+1", AmmoType::Bolt))
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build()
+}
+
+/// A handful of loose arrows or bolts, each its own item so that every shot
+/// can land somewhere different.
+fn ammunition(ecs: &mut World, x: i32, y: i32, ammo_type: AmmoType) {
+    let (name, count) = match ammo_type {
+        AmmoType::Arrow => ("Arrow", 6),
+        AmmoType::Bolt => ("Bolt", 4),
+    };
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437('|'),
+            fg: RGB::named(WHEAT),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: name.to_string(),
+        })
+        .with(Item {
+            weight: 0.1,
+            value: 1,
+        })
+        .with(Ammunition { ammo_type })
+        .with(Stack { count })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn shield(ecs: &mut World, x: i32, y: i32) -> Entity {
     ecs.create_entity()
         .with(Position { x, y })
         .with(Renderable {
@@ -277,16 +1171,20 @@ fn shield(ecs: &mut World, x: i32, y: i32) {
         .with(Name {
             name: "Shield".to_string(),
         })
-        .with(Item {})
+        .with(Item {
+            weight: 6.0,
+            value: 20,
+        })
         .with(Equippable {
             slot: EquipmentSlot::Shield,
         })
         .with(DefenseBonus { defense: 1 })
+        .with(BlockChance { percent: 10 })
         .marked::<SimpleMarker<SerializeMe>>()
-        .build();
+        .build()
 }
 
-fn tower_shield(ecs: &mut World, x: i32, y: i32) {
+fn tower_shield(ecs: &mut World, x: i32, y: i32) -> Entity {
     ecs.create_entity()
         .with(Position { x, y })
         .with(Renderable {
@@ -298,13 +1196,23 @@ fn tower_shield(ecs: &mut World, x: i32, y: i32) {
         .with(Name {
             name: "Tower Shield".to_string(),
         })
-        .with(Item {})
+        .with(Item {
+            weight: 15.0,
+            value: 40,
+        })
         .with(Equippable {
             slot: EquipmentSlot::Shield,
         })
-        .with(DefenseBonus { defense: 3 })
+        .with(DamageReduction { amount: 1 })
+        .with(BlockChance { percent: 30 })
+        .with(HeavyArmor {})
+        .with(AttributeBonus {
+            quickness: -1,
+            ..Default::default()
+        })
+        .with(resistances(&[(DamageType::Fire, Resistance::Resistant)]))
         .marked::<SimpleMarker<SerializeMe>>()
-        .build();
+        .build()
 }
 
 fn magic_mapping_scroll(ecs: &mut World, x: i32, y: i32) {
@@ -319,48 +1227,535 @@ fn magic_mapping_scroll(ecs: &mut World, x: i32, y: i32) {
         .with(Name {
             name: "Scroll of Magic Mapping".to_string(),
         })
-        .with(Item {})
+        .with(Item {
+            weight: 0.5,
+            value: 100,
+        })
         .with(MagicMapper {})
         .with(Consumable {})
         .marked::<SimpleMarker<SerializeMe>>()
         .build();
 }
 
-fn rations(ecs: &mut World, x: i32, y: i32) {
+fn remove_curse_scroll(ecs: &mut World, x: i32, y: i32) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437(')'),
+            fg: RGB::named(WHITE),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Remove Curse Scroll".to_string(),
+        })
+        .with(Item {
+            weight: 0.5,
+            value: 80,
+        })
+        .with(RemovesCurse {})
+        .with(Consumable {})
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn recharge_scroll(ecs: &mut World, x: i32, y: i32) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437(')'),
+            fg: RGB::named(LIGHT_BLUE),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Recharge Scroll".to_string(),
+        })
+        .with(Item {
+            weight: 0.5,
+            value: 100,
+        })
+        .with(Recharges {})
+        .with(Consumable {})
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn charges(ecs: &mut World, base: i32) -> Charges {
+    let uses = base
+        + ecs
+            .write_resource::<RandomNumberGenerator>()
+            .roll_dice(1, 3);
+    Charges {
+        uses,
+        max_uses: uses,
+    }
+}
+
+fn magic_missile_wand(ecs: &mut World, x: i32, y: i32) {
+    let charges = charges(ecs, 3);
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437('-'),
+            fg: RGB::named(CYAN),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Wand of Magic Missile".to_string(),
+        })
+        .with(Item {
+            weight: 0.5,
+            value: 150,
+        })
+        .with(charges)
+        .with(Ranged { range: 6 })
+        .with(InflictsDamage {
+            damage: 6,
+            damage_type: DamageType::Physical,
+        })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn fire_wand(ecs: &mut World, x: i32, y: i32) {
+    let charges = charges(ecs, 2);
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437('-'),
+            fg: RGB::named(ORANGE),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Wand of Fire".to_string(),
+        })
+        .with(Item {
+            weight: 0.5,
+            value: 200,
+        })
+        .with(charges)
+        .with(Ranged { range: 6 })
+        .with(InflictsDamage {
+            damage: 10,
+            damage_type: DamageType::Fire,
+        })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn confusion_staff(ecs: &mut World, x: i32, y: i32) {
+    let charges = charges(ecs, 4);
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437('|'),
+            fg: RGB::named(PINK),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Staff of Confusion".to_string(),
+        })
+        .with(Item {
+            weight: 4.0,
+            value: 200,
+        })
+        .with(charges)
+        .with(Ranged { range: 6 })
+        .with(InflictsStatus {
+            kind: StatusKind::Confused,
+            turns: 4,
+            potency: 0,
+        })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+/// Looks just like the real thing until it's too late.
+fn cursed_ring(ecs: &mut World, x: i32, y: i32) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437('='),
+            fg: RGB::named(GOLD),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Ring of Quickness".to_string(),
+        })
+        .with(Item {
+            weight: 0.1,
+            value: 100,
+        })
+        .with(Equippable {
+            slot: EquipmentSlot::Ring1,
+        })
+        .with(AttributeBonus {
+            quickness: -2,
+            ..Default::default()
+        })
+        .with(Cursed { known: false })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn cursed_longsword(ecs: &mut World, x: i32, y: i32) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437('/'),
+            fg: RGB::named(YELLOW),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Longsword".to_string(),
+        })
+        .with(Item {
+            weight: 3.0,
+            value: 30,
+        })
+        .with(Equippable {
+            slot: EquipmentSlot::Melee,
+        })
+        .with(gamesystem::melee_weapon(-2, "1d8-1"))
+        .with(Cursed { known: false })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn food(ecs: &mut World, x: i32, y: i32, name: &str, fg: RGB, nutrition: i32, weight: f32) {
     ecs.create_entity()
         .with(Position { x, y })
         .with(Renderable {
             glyph: to_cp437('%'),
-            fg: RGB::named(GREEN),
+            fg,
             bg: RGB::named(BLACK),
             render_order: 2,
         })
         .with(Name {
-            name: "Rations".to_string(),
+            name: name.to_string(),
+        })
+        .with(Item {
+            weight,
+            value: 1 + nutrition / 200,
         })
-        .with(Item {})
-        .with(ProvidesFood {})
+        .with(ProvidesFood { nutrition })
         .with(Consumable {})
         .marked::<SimpleMarker<SerializeMe>>()
         .build();
 }
 
-fn bear_trap(ecs: &mut World, x: i32, y: i32) {
+fn spellbook(ecs: &mut World, x: i32, y: i32, spell: &str) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437('¶'),
+            fg: RGB::named(DODGERBLUE),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: format!("Spellbook of {}", spell),
+        })
+        .with(Item {
+            weight: 2.0,
+            value: 150,
+        })
+        .with(Spellbook {
+            spell: spell.to_string(),
+        })
+        .with(Consumable {})
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+/// Teaches `owner` a spell by name. Spells reuse the effect components of the
+/// scrolls they are modelled on.
+pub fn learn_spell(ecs: &mut World, owner: Entity, spell: &str) {
+    let builder = ecs.create_entity().with(Name {
+        name: spell.to_string(),
+    });
+    let builder = match spell {
+        "Magic Missile" => builder
+            .with(KnownSpell {
+                owner,
+                mana_cost: 2,
+            })
+            .with(Ranged { range: 6 })
+            .with(InflictsDamage {
+                damage: 6,
+                damage_type: DamageType::Physical,
+            }),
+        "Fireball" => builder
+            .with(KnownSpell {
+                owner,
+                mana_cost: 6,
+            })
+            .with(Ranged { range: 6 })
+            .with(InflictsDamage {
+                damage: 12,
+                damage_type: DamageType::Fire,
+            })
+            .with(AreaOfEffect { radius: 2 }),
+        "Confusion" => builder
+            .with(KnownSpell {
+                owner,
+                mana_cost: 3,
+            })
+            .with(Ranged { range: 4 })
+            .with(InflictsStatus {
+                kind: StatusKind::Confused,
+                turns: 4,
+                potency: 0,
+            }),
+        "Heal" => builder
+            .with(KnownSpell {
+                owner,
+                mana_cost: 4,
+            })
+            .with(ProvidesHealing { heal_amount: 8 }),
+        _ => return,
+    };
+    builder.marked::<SimpleMarker<SerializeMe>>().build();
+}
+
+/// The parts every hidden trap shares; callers add what it does when sprung.
+fn trap<'a>(ecs: &'a mut World, x: i32, y: i32, name: &str, fg: RGB) -> EntityBuilder<'a> {
     ecs.create_entity()
         .with(Position { x, y })
         .with(Renderable {
             glyph: to_cp437('^'),
-            fg: RGB::named(RED),
+            fg,
             bg: RGB::named(BLACK),
             render_order: 2,
         })
         .with(Name {
-            name: "Bear Trap".to_string(),
+            name: name.to_string(),
         })
         .with(Hidden {})
         .with(EntryTrigger {})
-        .with(InflictsDamage { damage: 6 })
+}
+
+fn bear_trap(ecs: &mut World, x: i32, y: i32) {
+    trap(ecs, x, y, "Bear Trap", RGB::named(RED))
+        .with(InflictsDamage {
+            damage: 6,
+            damage_type: DamageType::Physical,
+        })
+        .with(SingleActivation {})
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn teleport_trap(ecs: &mut World, x: i32, y: i32) {
+    trap(ecs, x, y, "Teleport Trap", RGB::named(MAGENTA))
+        .with(Teleports {})
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn alarm_trap(ecs: &mut World, x: i32, y: i32) {
+    trap(ecs, x, y, "Alarm Trap", RGB::named(YELLOW))
+        .with(Alarm {})
         .with(SingleActivation {})
         .marked::<SimpleMarker<SerializeMe>>()
         .build();
 }
+
+fn confusion_gas_trap(ecs: &mut World, x: i32, y: i32) {
+    trap(ecs, x, y, "Confusion Gas Trap", RGB::named(PINK))
+        .with(InflictsStatus {
+            kind: StatusKind::Confused,
+            turns: 4,
+            potency: 0,
+        })
+        .with(AreaOfEffect { radius: 2 })
+        .with(SingleActivation {})
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn trapdoor(ecs: &mut World, x: i32, y: i32) {
+    trap(ecs, x, y, "Trapdoor", RGB::named(BURLYWOOD))
+        .with(Trapdoor {})
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+fn summon_ally_scroll(ecs: &mut World, x: i32, y: i32) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: to_cp437(')'),
+            fg: RGB::named(GREEN),
+            bg: RGB::named(BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: "Summon Ally Scroll".to_string(),
+        })
+        .with(Item {
+            weight: 0.5,
+            value: 120,
+        })
+        .with(SummonsAlly {})
+        .with(Consumable {})
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specs::saveload::SimpleMarkerAllocator;
+
+    macro_rules! components {
+        ($($component:ty),* $(,)?) => {
+            fn test_world() -> World {
+                let mut ecs = World::new();
+                $(ecs.register::<$component>();)*
+                ecs.register::<SimpleMarker<SerializeMe>>();
+                ecs.insert(SimpleMarkerAllocator::<SerializeMe>::new());
+                ecs
+            }
+
+            fn components_of(ecs: &World, entity: Entity) -> Vec<&'static str> {
+                let mut found = Vec::new();
+                $(
+                    if ecs.read_storage::<$component>().get(entity).is_some() {
+                        found.push(stringify!($component));
+                    }
+                )*
+                found
+            }
+        };
+    }
+
+    // Everything main() registers, so a component missing from split_stack's
+    // copy list shows up here as soon as a stackable item carries it
+    components!(
+        Position,
+        Renderable,
+        Player,
+        Viewshed,
+        Monster,
+        Name,
+        BlocksTile,
+        CombatStats,
+        SufferDamage,
+        WantsToMelee,
+        Item,
+        ProvidesHealing,
+        InBackpack,
+        WantsToPickupItem,
+        WantsToUseItem,
+        WantsToDropItem,
+        Consumable,
+        Ranged,
+        InflictsDamage,
+        AreaOfEffect,
+        StatusEffects,
+        InflictsStatus,
+        Equippable,
+        Equipped,
+        MeleePowerBonus,
+        DefenseBonus,
+        WantsToRemoveItem,
+        ParticleLifetime,
+        MagicMapper,
+        HungerClock,
+        ProvidesFood,
+        Hidden,
+        EntryTrigger,
+        EntityMoved,
+        SingleActivation,
+        Alertness,
+        PackMember,
+        Ally,
+        AllyTarget,
+        SummonsAlly,
+        Tracker,
+        Attributes,
+        Skills,
+        AttributeBonus,
+        MeleeWeapon,
+        Resistances,
+        RangedWeapon,
+        Ammunition,
+        WantsToShoot,
+        Potion,
+        Throwable,
+        WantsToThrow,
+        TwoHanded,
+        DamageReduction,
+        HeavyArmor,
+        BlockChance,
+        Encumbrance,
+        Cursed,
+        RemovesCurse,
+        Charges,
+        Recharges,
+        MagicItem,
+        Stack,
+        Mana,
+        KnownSpell,
+        Spellbook,
+        WantsToCastSpell,
+        Teleports,
+        Alarm,
+        Trapdoor,
+    );
+
+    #[test]
+    fn split_stack_keeps_every_component() {
+        let stackables: &[fn(&mut World, i32, i32)] = &[
+            health_potion,
+            fireball_scroll,
+            magic_missile_scroll,
+            confusion_scroll,
+            haste_potion,
+            regeneration_potion,
+            poison_cloud_scroll,
+            slow_scroll,
+            paralysis_scroll,
+            blindness_scroll,
+            magic_mapping_scroll,
+            remove_curse_scroll,
+            recharge_scroll,
+            summon_ally_scroll,
+            |ecs, x, y| food(ecs, x, y, "Rations", RGB::named(GREEN), 800, 0.5),
+            |ecs, x, y| spellbook(ecs, x, y, "Fireball"),
+            |ecs, x, y| ammunition(ecs, x, y, AmmoType::Arrow),
+        ];
+        for spawn in stackables {
+            let mut ecs = test_world();
+            spawn(&mut ecs, 1, 1);
+            let item = (&ecs.entities(), &ecs.read_storage::<Item>())
+                .join()
+                .map(|(item, _item)| item)
+                .next()
+                .expect("Nothing was spawned");
+            let owner = ecs.create_entity().build();
+            ecs.write_storage::<Position>().remove(item);
+            ecs.write_storage::<InBackpack>()
+                .insert(item, InBackpack { owner })
+                .expect("Unable to insert");
+            ecs.write_storage::<Stack>()
+                .insert(item, Stack { count: 3 })
+                .expect("Unable to insert");
+
+            let single = split_stack(&mut ecs, item);
+
+            assert_ne!(single, item);
+            assert_eq!(ecs.read_storage::<Stack>().get(item).unwrap().count, 2);
+            let mut expected = components_of(&ecs, item);
+            expected.retain(|c| *c != "Stack");
+            assert_eq!(components_of(&ecs, single), expected);
+        }
+    }
+}
@@ -1,10 +1,20 @@
 use super::*;
+pub mod ally_ai_system;
+pub mod attribute_system;
 pub mod damage_system;
 pub mod hunger_system;
 pub mod inventory_system;
+pub mod item_ai_system;
 pub mod map_indexing_system;
 pub mod melee_combat_system;
 pub mod monster_ai_system;
+pub mod noise_system;
 pub mod particle_system;
+pub mod ranged_combat_system;
+pub mod regen_system;
+pub mod scent_system;
+pub mod spell_system;
+pub mod status_system;
+pub mod throw_system;
 pub mod trigger_system;
 pub mod visibility_system;
@@ -0,0 +1,137 @@
+use super::{
+    Ally, AllyOrder, AllyTarget, EntityMoved, Map, Monster, Position, RunState, StatusEffects,
+    StatusKind, Viewshed, WantsToMelee,
+};
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+pub struct AllyAI {}
+
+impl<'a> System<'a> for AllyAI {
+    type SystemData = (
+        WriteExpect<'a, Map>,
+        ReadExpect<'a, Point>,
+        ReadExpect<'a, RunState>,
+        Entities<'a>,
+        WriteStorage<'a, Viewshed>,
+        ReadStorage<'a, Ally>,
+        WriteStorage<'a, AllyTarget>,
+        ReadStorage<'a, Monster>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, WantsToMelee>,
+        WriteStorage<'a, EntityMoved>,
+        ReadStorage<'a, StatusEffects>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut map,
+            player_pos,
+            runstate,
+            entities,
+            mut viewshed,
+            allies,
+            mut ally_targets,
+            monsters,
+            mut position,
+            mut wants_to_melee,
+            mut entity_moved,
+            statuses,
+        ) = data;
+
+        if *runstate != RunState::MonsterTurn {
+            return;
+        }
+
+        // Forget about targets that have since died
+        let mut stale: Vec<Entity> = Vec::new();
+        for (entity, ally_target) in (&entities, &ally_targets).join() {
+            if !entities.is_alive(ally_target.target) {
+                stale.push(entity);
+            }
+        }
+        for entity in stale.iter() {
+            ally_targets.remove(*entity);
+        }
+
+        // Where every hostile is standing, so that allies can pick a fight
+        let hostiles: Vec<(Entity, Point)> = (&entities, &monsters, &position)
+            .join()
+            .map(|(entity, _monster, pos)| (entity, Point::new(pos.x, pos.y)))
+            .collect();
+
+        for (entity, viewshed, ally, pos) in
+            (&entities, &mut viewshed, &allies, &mut position).join()
+        {
+            if statuses
+                .get(entity)
+                .is_some_and(|s| s.loses_turn() || s.has(StatusKind::Confused))
+            {
+                continue;
+            }
+
+            let my_pos = Point::new(pos.x, pos.y);
+
+            let ordered_target = ally_targets.get(entity).and_then(|t| {
+                hostiles
+                    .iter()
+                    .find(|hostile| hostile.0 == t.target)
+                    .cloned()
+            });
+            let target = match ally.order {
+                AllyOrder::Attack if ordered_target.is_some() => ordered_target,
+                AllyOrder::Stay => closest(&hostiles, my_pos, |p| {
+                    DistanceAlg::Pythagoras.distance2d(my_pos, p) < 1.5
+                }),
+                _ => closest(&hostiles, my_pos, |p| viewshed.visible_tiles.contains(&p)),
+            };
+
+            let mut move_target: Option<usize> = None;
+            if let Some((target, target_pos)) = target {
+                if DistanceAlg::Pythagoras.distance2d(my_pos, target_pos) < 1.5 {
+                    wants_to_melee
+                        .insert(entity, WantsToMelee { target })
+                        .expect("Unable to insert ally attack");
+                } else if ally.order != AllyOrder::Stay {
+                    move_target = Some(map.xy_idx(target_pos.x, target_pos.y));
+                }
+            } else if ally.order != AllyOrder::Stay
+                && DistanceAlg::Pythagoras.distance2d(my_pos, *player_pos) > 2.5
+            {
+                move_target = Some(map.xy_idx(player_pos.x, player_pos.y));
+            }
+
+            if let Some(target_idx) = move_target {
+                let path = a_star_search(map.xy_idx(pos.x, pos.y) as i32, target_idx as i32, &*map);
+                if path.success && path.steps.len() > 1 {
+                    let mut idx = map.xy_idx(pos.x, pos.y);
+                    map.blocked[idx] = false;
+                    pos.x = path.steps[1] as i32 % map.width;
+                    pos.y = path.steps[1] as i32 / map.width;
+                    idx = map.xy_idx(pos.x, pos.y);
+                    map.blocked[idx] = true;
+                    viewshed.dirty = true;
+                    entity_moved
+                        .insert(entity, EntityMoved {})
+                        .expect("Failed to insert marker.");
+                }
+            }
+        }
+    }
+}
+
+fn closest<F: Fn(Point) -> bool>(
+    hostiles: &[(Entity, Point)],
+    from: Point,
+    allowed: F,
+) -> Option<(Entity, Point)> {
+    hostiles
+        .iter()
+        .filter(|hostile| allowed(hostile.1))
+        .min_by(|a, b| {
+            let da = DistanceAlg::Pythagoras.distance2d(from, a.1);
+            let db = DistanceAlg::Pythagoras.distance2d(from, b.1);
+            da.partial_cmp(&db).unwrap()
+        })
+        .cloned()
+}
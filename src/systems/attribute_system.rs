@@ -0,0 +1,106 @@
+use super::{
+    gamelog::GameLog,
+    gamesystem::{
+        attr_bonus, carried_weight, carry_capacity, mana_at_level, npc_hp, player_hp_at_level,
+        OVERLOAD_PENALTY,
+    },
+    AttributeBonus, Attributes, CombatStats, Encumbrance, Equipped, InBackpack, Item, Mana, Stack,
+};
+use specs::prelude::*;
+
+/// Folds the attribute bonuses of worn equipment into everyone's attributes,
+/// weighs what they carry, and keeps maximum hit points in line with fitness
+/// and level.
+pub struct AttributeSystem {}
+
+impl<'a> System<'a> for AttributeSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        WriteStorage<'a, Attributes>,
+        WriteStorage<'a, CombatStats>,
+        ReadStorage<'a, AttributeBonus>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, InBackpack>,
+        WriteStorage<'a, Encumbrance>,
+        WriteExpect<'a, GameLog>,
+        ReadStorage<'a, Stack>,
+        WriteStorage<'a, Mana>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player_entity,
+            mut attributes,
+            mut combat_stats,
+            attribute_bonuses,
+            equipped,
+            items,
+            backpack,
+            mut encumbrance,
+            mut log,
+            stacks,
+            mut mana,
+        ) = data;
+
+        for (entity, attrs, stats) in (&entities, &mut attributes, &mut combat_stats).join() {
+            let mut modifiers = AttributeBonus::default();
+            for (bonus, equipped_by) in (&attribute_bonuses, &equipped).join() {
+                if equipped_by.owner == entity {
+                    modifiers.might += bonus.might;
+                    modifiers.fitness += bonus.fitness;
+                    modifiers.quickness += bonus.quickness;
+                    modifiers.intelligence += bonus.intelligence;
+                }
+            }
+
+            attrs.might.modifiers = modifiers.might;
+            attrs.fitness.modifiers = modifiers.fitness;
+            attrs.quickness.modifiers = modifiers.quickness;
+            attrs.intelligence.modifiers = modifiers.intelligence;
+
+            // Hauling too much leaves you clumsy, both dodging and aiming
+            let burden = Encumbrance {
+                weight: carried_weight(entity, &items, &backpack, &equipped, &stacks),
+                capacity: carry_capacity(attrs.might.value()),
+            };
+            if burden.overloaded() {
+                attrs.quickness.modifiers -= OVERLOAD_PENALTY;
+            }
+            if entity == *player_entity {
+                let was_overloaded = encumbrance.get(entity).is_some_and(|e| e.overloaded());
+                if burden.overloaded() && !was_overloaded {
+                    log.entries
+                        .push("You are overloaded; you feel clumsy and slow to react.".to_string());
+                } else if !burden.overloaded() && was_overloaded {
+                    log.entries
+                        .push("You are no longer overloaded.".to_string());
+                }
+            }
+            encumbrance
+                .insert(entity, burden)
+                .expect("Unable to insert encumbrance");
+            for attr in [
+                &mut attrs.might,
+                &mut attrs.fitness,
+                &mut attrs.quickness,
+                &mut attrs.intelligence,
+            ] {
+                attr.bonus = attr_bonus(attr.value());
+            }
+
+            stats.max_hp = if entity == *player_entity {
+                player_hp_at_level(attrs.fitness.value(), stats.level)
+            } else {
+                npc_hp(attrs.fitness.value(), stats.level)
+            };
+            stats.hp = i32::min(stats.hp, stats.max_hp);
+            if let Some(pool) = mana.get_mut(entity) {
+                pool.max_mana = mana_at_level(attrs.intelligence.value(), stats.level);
+                pool.mana = i32::min(pool.mana, pool.max_mana);
+            }
+        }
+    }
+}
@@ -1,4 +1,11 @@
-use super::{gamelog::GameLog, CombatStats, Map, Name, Player, Position, RunState, SufferDamage};
+use super::{
+    gamelog::GameLog,
+    gamesystem::{damage_reduction, npc_hp, player_hp_at_level, xp_for_kill, xp_to_level_up},
+    Alertness, AllyTarget, Attributes, CombatStats, DamageReduction, DamageType, Equipped,
+    InBackpack, Map, Name, ParticleBuilder, Player, Position, Resistance, Resistances, RunState,
+    Skills, SufferDamage,
+};
+use bracket_lib::prelude::*;
 use specs::prelude::*;
 
 pub struct DamageSystem {}
@@ -10,21 +17,186 @@ impl<'a> System<'a> for DamageSystem {
         ReadStorage<'a, Position>,
         WriteExpect<'a, Map>,
         Entities<'a>,
+        WriteStorage<'a, Alertness>,
+        ReadExpect<'a, Entity>,
+        WriteStorage<'a, Attributes>,
+        WriteStorage<'a, Skills>,
+        WriteExpect<'a, GameLog>,
+        WriteExpect<'a, ParticleBuilder>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        ReadStorage<'a, Resistances>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, DamageReduction>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut stats, mut damage, positions, mut map, entities) = data;
+        let (
+            mut stats,
+            mut damage,
+            positions,
+            mut map,
+            entities,
+            mut alertness,
+            player_entity,
+            mut attributes,
+            mut skills,
+            mut log,
+            mut particle_builder,
+            mut rng,
+            resistances,
+            equipped,
+            names,
+            reductions,
+        ) = data;
 
-        for (entity, mut stats, damage) in (&entities, &mut stats, &damage).join() {
-            stats.hp -= damage.amount.iter().sum::<i32>();
+        let mut xp_gains: Vec<(Entity, i32)> = Vec::new();
+        for (entity, stats, damage) in (&entities, &mut stats, &damage).join() {
+            let was_alive = stats.hp > 0;
             let pos = positions.get(entity);
+            let visible = pos.is_some_and(|pos| map.visible_tiles[map.xy_idx(pos.x, pos.y)]);
+            let soak = damage_reduction(entity, &reductions, &equipped);
+            for (amount, damage_type, _from, weapon) in damage.amount.iter() {
+                // Armor only helps against weapon blows, not fire, poison,
+                // magic or an empty stomach
+                let amount = match damage_type {
+                    DamageType::Physical if *weapon => i32::max(0, *amount - soak),
+                    _ => *amount,
+                };
+                let resistance = resistance_to(entity, *damage_type, &resistances, &equipped);
+                stats.hp -= match resistance {
+                    None => amount,
+                    Some(Resistance::Resistant) => amount / 2,
+                    Some(Resistance::Immune) => 0,
+                    Some(Resistance::Vulnerable) => amount * 2,
+                };
+
+                if let (Some(resistance), true) = (resistance, visible) {
+                    let (you, it) = match resistance {
+                        Resistance::Resistant => ("resist", "resists"),
+                        Resistance::Immune => ("are immune to", "is immune to"),
+                        Resistance::Vulnerable => ("are vulnerable to", "is vulnerable to"),
+                    };
+                    if entity == *player_entity {
+                        log.entries
+                            .push(format!("You {} the {}.", you, damage_type.name()));
+                    } else if let Some(name) = names.get(entity) {
+                        log.entries.push(format!(
+                            "The {} {} the {}.",
+                            name.name,
+                            it,
+                            damage_type.name()
+                        ));
+                    }
+                }
+            }
+            alertness.remove(entity);
             if let Some(pos) = pos {
                 let idx = map.xy_idx(pos.x, pos.y);
                 map.bloodstains.insert(idx);
             }
+
+            // Whoever landed the killing blow earns the experience
+            if was_alive && stats.hp < 1 {
+                if let Some(killer) = damage.amount.iter().rev().find_map(|d| d.2) {
+                    if killer != entity {
+                        xp_gains.push((killer, xp_for_kill(stats.level)));
+                    }
+                }
+            }
         }
 
         damage.clear();
+
+        for (killer, xp) in xp_gains {
+            let Some(stats) = stats.get_mut(killer) else {
+                continue;
+            };
+            if stats.hp < 1 {
+                continue;
+            }
+            stats.xp += xp;
+            while stats.xp >= xp_to_level_up(stats.level) {
+                stats.level += 1;
+
+                // Every level brings a point in one attribute and in every skill
+                if let Some(attrs) = attributes.get_mut(killer) {
+                    let attr = match rng.roll_dice(1, 4) {
+                        1 => &mut attrs.might,
+                        2 => &mut attrs.fitness,
+                        3 => &mut attrs.quickness,
+                        _ => &mut attrs.intelligence,
+                    };
+                    attr.base += 1;
+                    stats.max_hp = if killer == *player_entity {
+                        player_hp_at_level(attrs.fitness.value(), stats.level)
+                    } else {
+                        npc_hp(attrs.fitness.value(), stats.level)
+                    };
+                }
+                if let Some(skills) = skills.get_mut(killer) {
+                    for level in skills.skills.values_mut() {
+                        *level += 1;
+                    }
+                }
+                stats.hp = stats.max_hp;
+
+                if killer == *player_entity {
+                    log.entries
+                        .push(format!("Welcome to level {}!", stats.level));
+                    if let Some(pos) = positions.get(killer) {
+                        for dy in -1..=1 {
+                            for dx in -1..=1 {
+                                particle_builder.request(
+                                    pos.x + dx,
+                                    pos.y + dy,
+                                    RGB::named(GOLD),
+                                    RGB::named(BLACK),
+                                    to_cp437('*'),
+                                    400.0,
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How well `entity` stands up to `damage_type`, counting both its own hide
+/// and whatever it is wearing. Immunity wins outright; otherwise resistances
+/// and vulnerabilities cancel each other out.
+fn resistance_to(
+    entity: Entity,
+    damage_type: DamageType,
+    resistances: &ReadStorage<Resistances>,
+    equipped: &ReadStorage<Equipped>,
+) -> Option<Resistance> {
+    let mut sources: Vec<Resistance> = Vec::new();
+    if let Some(own) = resistances.get(entity) {
+        sources.extend(own.resistances.get(&damage_type));
+    }
+    for (worn, equipped_by) in (resistances, equipped).join() {
+        if equipped_by.owner == entity {
+            sources.extend(worn.resistances.get(&damage_type));
+        }
+    }
+
+    if sources.contains(&Resistance::Immune) {
+        return Some(Resistance::Immune);
+    }
+    let balance: i32 = sources
+        .iter()
+        .map(|r| match r {
+            Resistance::Vulnerable => -1,
+            _ => 1,
+        })
+        .sum();
+    match balance {
+        b if b > 0 => Some(Resistance::Resistant),
+        b if b < 0 => Some(Resistance::Vulnerable),
+        _ => None,
     }
 }
 
@@ -56,6 +228,50 @@ pub fn delete_the_dead(ecs: &mut World) {
         }
     }
 
+    // Whatever the dead were carrying falls to the floor
+    {
+        let entities = ecs.entities();
+        let mut positions = ecs.write_storage::<Position>();
+        let mut backpack = ecs.write_storage::<InBackpack>();
+        let mut equipped = ecs.write_storage::<Equipped>();
+        let mut to_drop: Vec<(Entity, Position)> = Vec::new();
+        for victim in dead.iter() {
+            if let Some(pos) = positions.get(*victim) {
+                for (item, carried) in (&entities, &backpack).join() {
+                    if carried.owner == *victim {
+                        to_drop.push((item, pos.clone()));
+                    }
+                }
+                for (item, worn) in (&entities, &equipped).join() {
+                    if worn.owner == *victim {
+                        to_drop.push((item, pos.clone()));
+                    }
+                }
+            }
+        }
+        for (item, pos) in to_drop {
+            backpack.remove(item);
+            equipped.remove(item);
+            positions
+                .insert(item, pos)
+                .expect("Unable to drop a dead entity's item.");
+        }
+    }
+
+    // Allies stop chasing anything that has died
+    {
+        let entities = ecs.entities();
+        let mut ally_targets = ecs.write_storage::<AllyTarget>();
+        let stale: Vec<Entity> = (&entities, &ally_targets)
+            .join()
+            .filter(|(_e, t)| dead.contains(&t.target))
+            .map(|(e, _t)| e)
+            .collect();
+        for ally in stale {
+            ally_targets.remove(ally);
+        }
+    }
+
     for victim in dead {
         ecs.delete_entity(victim)
             .expect("Failed to delete dead entity.");
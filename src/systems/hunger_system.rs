@@ -1,4 +1,7 @@
-use super::{gamelog::GameLog, HungerClock, HungerState, RunState, SufferDamage};
+use super::{
+    gamelog::GameLog, gamesystem::hunger_state, DamageType, EffectQueue, EffectType, HungerClock,
+    HungerState, RunState, Targets,
+};
 use specs::prelude::*;
 
 pub struct HungerSystem;
@@ -9,15 +12,14 @@ impl<'a> System<'a> for HungerSystem {
         WriteStorage<'a, HungerClock>,
         ReadExpect<'a, Entity>,
         ReadExpect<'a, RunState>,
-        WriteStorage<'a, SufferDamage>,
+        WriteExpect<'a, EffectQueue>,
         WriteExpect<'a, GameLog>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (entities, mut hunger_clock, player_entity, runstate, mut inflict_damage, mut log) =
-            data;
+        let (entities, mut hunger_clock, player_entity, runstate, mut effects, mut log) = data;
 
-        for (entity, mut clock) in (&entities, &mut hunger_clock).join() {
+        for (entity, clock) in (&entities, &mut hunger_clock).join() {
             let mut proceed = false;
 
             match *runstate {
@@ -35,37 +37,44 @@ impl<'a> System<'a> for HungerSystem {
             }
 
             if proceed {
-                clock.duration -= 1;
-                if clock.duration < 1 {
-                    match clock.state {
-                        HungerState::WellFed => {
-                            clock.state = HungerState::Normal;
-                            clock.duration = 200;
-                            if entity == *player_entity {
-                                log.entries.push("You are no longer well fed.".to_string());
-                            }
+                clock.satiation = i32::max(0, clock.satiation - 1);
+                let state = hunger_state(clock.satiation);
+                if state != clock.state && entity == *player_entity {
+                    let message = match (clock.state, state) {
+                        (_, HungerState::Gorged) => "You are stuffed.",
+                        (HungerState::Gorged, HungerState::WellFed) => {
+                            "You no longer feel stuffed."
                         }
-                        HungerState::Normal => {
-                            clock.state = HungerState::Hungry;
-                            clock.duration = 200;
-                            if entity == *player_entity {
-                                log.entries.push("You are hungry.".to_string());
-                            }
+                        (_, HungerState::WellFed) => "You are well fed.",
+                        (HungerState::Gorged | HungerState::WellFed, HungerState::Normal) => {
+                            "You are no longer well fed."
                         }
-                        HungerState::Hungry => {
-                            clock.state = HungerState::Starving;
-                            clock.duration = 200;
-                            if entity == *player_entity {
-                                log.entries.push("You are starving!".to_string());
-                            }
+                        (_, HungerState::Normal) => "You are no longer hungry.",
+                        (HungerState::Starving, HungerState::Hungry) => {
+                            "You are no longer starving."
                         }
-                        HungerState::Starving => {
-                            if entity == *player_entity {
-                                log.entries.push("Your hunger pangs are getting painful! You suffer 1 hp damage.".to_string());
-                            }
-                            SufferDamage::new_damage(&mut inflict_damage, entity, 1);
-                        }
-                    }
+                        (_, HungerState::Hungry) => "You are hungry.",
+                        (_, HungerState::Starving) => "You are starving!",
+                    };
+                    log.entries.push(message.to_string());
+                }
+                clock.state = state;
+
+                // Starving monsters only get desperate for food; the player wastes away
+                if state == HungerState::Starving && entity == *player_entity {
+                    log.entries.push(
+                        "Your hunger pangs are getting painful! You suffer 1 hp damage."
+                            .to_string(),
+                    );
+                    effects.add_effect(
+                        None,
+                        EffectType::Damage {
+                            amount: 1,
+                            damage_type: DamageType::Physical,
+                            weapon: false,
+                        },
+                        Targets::Single { target: entity },
+                    );
                 }
             }
         }
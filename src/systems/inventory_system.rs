@@ -10,28 +10,100 @@ impl<'a> System<'a> for ItemCollectionSystem {
         WriteStorage<'a, Position>,
         ReadStorage<'a, Name>,
         WriteStorage<'a, InBackpack>,
+        ReadExpect<'a, Map>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, Encumbrance>,
+        ReadExpect<'a, Identification>,
+        Entities<'a>,
+        ReadStorage<'a, Consumable>,
+        WriteStorage<'a, Stack>,
+        ReadStorage<'a, Ammunition>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (player_entity, mut gamelog, mut wants_pickup, mut positions, names, mut backpack) =
-            data;
+        let (
+            player_entity,
+            mut gamelog,
+            mut wants_pickup,
+            mut positions,
+            names,
+            mut backpack,
+            map,
+            items,
+            encumbrance,
+            identification,
+            entities,
+            consumables,
+            mut stacks,
+            ammunition,
+        ) = data;
 
         for pickup in wants_pickup.join() {
-            positions.remove(pickup.item);
-            backpack
-                .insert(
-                    pickup.item,
-                    InBackpack {
-                        owner: pickup.collected_by,
-                    },
-                )
-                .expect("Failed to add item to the backpack.");
+            let item_pos = positions.remove(pickup.item);
+            let item_pos = match item_pos {
+                // Someone else got to it first this turn
+                None => continue,
+                Some(item_pos) => item_pos,
+            };
+            let count = stacks.get(pickup.item).map_or(1, |s| s.count);
 
             if pickup.collected_by == *player_entity {
                 gamelog.entries.push(format!(
                     "You pick up the {}",
-                    names.get(pickup.item).unwrap().name
+                    identification.display_name(&names.get(pickup.item).unwrap().name)
                 ));
+                if let (Some(burden), Some(item)) =
+                    (encumbrance.get(pickup.collected_by), items.get(pickup.item))
+                {
+                    if !burden.overloaded()
+                        && burden.weight + item.weight * count as f32 > burden.capacity
+                    {
+                        gamelog
+                            .entries
+                            .push("That is more than you can comfortably carry!".to_string());
+                    }
+                }
+            } else if map.visible_tiles[map.xy_idx(item_pos.x, item_pos.y)] {
+                if let Some(collector) = names.get(pickup.collected_by) {
+                    gamelog.entries.push(format!(
+                        "The {} picks up the {}.",
+                        collector.name,
+                        identification.display_name(&names.get(pickup.item).unwrap().name)
+                    ));
+                }
+            }
+
+            // Identical consumables and ammunition share a single backpack entry
+            let stackable =
+                |item: Entity| consumables.get(item).is_some() || ammunition.get(item).is_some();
+            let name = &names.get(pickup.item).unwrap().name;
+            let existing = (&entities, &backpack, &names)
+                .join()
+                .find(|(item, carried, item_name)| {
+                    *item != pickup.item
+                        && carried.owner == pickup.collected_by
+                        && item_name.name == *name
+                        && stackable(*item)
+                })
+                .map(|(item, ..)| item);
+            match existing {
+                Some(stack) if stackable(pickup.item) => {
+                    let total = stacks.get(stack).map_or(1, |s| s.count) + count;
+                    stacks
+                        .insert(stack, Stack { count: total })
+                        .expect("Failed to stack item.");
+                    entities.delete(pickup.item).expect("Delete failed");
+                }
+                _ => {
+                    backpack
+                        .insert(
+                            pickup.item,
+                            InBackpack {
+                                owner: pickup.collected_by,
+                            },
+                        )
+                        .expect("Failed to add item to the backpack.");
+                }
             }
         }
 
@@ -45,26 +117,19 @@ impl<'a> System<'a> for ItemUseSystem {
     type SystemData = (
         ReadExpect<'a, Entity>,
         WriteExpect<'a, gamelog::GameLog>,
-        WriteExpect<'a, Map>,
+        ReadExpect<'a, Map>,
         Entities<'a>,
         WriteStorage<'a, WantsToUseItem>,
         ReadStorage<'a, Name>,
-        ReadStorage<'a, Consumable>,
-        ReadStorage<'a, ProvidesHealing>,
-        ReadStorage<'a, InflictsDamage>,
-        WriteStorage<'a, CombatStats>,
-        WriteStorage<'a, SufferDamage>,
         ReadStorage<'a, AreaOfEffect>,
-        WriteStorage<'a, Confusion>,
         ReadStorage<'a, Equippable>,
         WriteStorage<'a, Equipped>,
         WriteStorage<'a, InBackpack>,
-        WriteExpect<'a, ParticleBuilder>,
         ReadStorage<'a, Position>,
-        ReadStorage<'a, MagicMapper>,
-        WriteExpect<'a, RunState>,
-        ReadStorage<'a, ProvidesFood>,
-        WriteStorage<'a, HungerClock>,
+        WriteExpect<'a, EffectQueue>,
+        ReadStorage<'a, TwoHanded>,
+        ReadStorage<'a, MeleeWeapon>,
+        WriteStorage<'a, Cursed>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -75,83 +140,80 @@ impl<'a> System<'a> for ItemUseSystem {
             entities,
             mut wants_use,
             names,
-            consumables,
-            healing,
-            inflict_damage,
-            mut combat_stats,
-            mut suffer_damage,
             aoe,
-            mut confused,
             equippable,
             mut equipped,
             mut backpack,
-            mut particle_builder,
             positions,
-            magic_mapper,
-            mut runstate,
-            provides_food,
-            mut hunger_clocks,
+            mut effects,
+            two_handed,
+            melee_weapons,
+            mut cursed,
         ) = data;
 
-        for (entity, useitem) in (&entities, &wants_use).join() {
-            let mut used_item = true;
+        let is_visible = |e: Entity| match positions.get(e) {
+            Some(pos) => map.visible_tiles[map.xy_idx(pos.x, pos.y)],
+            None => false,
+        };
 
-            let mut targets: Vec<Entity> = Vec::new();
-            match useitem.target {
-                None => targets.push(*player_entity),
-                Some(target) => {
-                    let area_effect = aoe.get(useitem.item);
-                    match area_effect {
-                        None => {
-                            let idx = map.xy_idx(target.x, target.y);
-                            for mob in map.tile_content[idx].iter() {
-                                targets.push(*mob);
-                            }
-                        }
-                        Some(area_effect) => {
-                            let mut blast_tiles = field_of_view(target, area_effect.radius, &*map);
-                            blast_tiles.retain(|p| {
-                                p.x > 0 && p.x < map.width - 1 && p.y > 0 && p.y < map.height - 1
-                            });
-                            for tile_idx in blast_tiles.iter() {
-                                let idx = map.xy_idx(tile_idx.x, tile_idx.y);
-                                for mob in map.tile_content[idx].iter() {
-                                    targets.push(*mob);
-                                }
-                                particle_builder.request(
-                                    tile_idx.x,
-                                    tile_idx.y,
-                                    RGB::named(ORANGE),
-                                    RGB::named(BLACK),
-                                    to_cp437('░'),
-                                    200.0,
-                                );
+        for (entity, useitem) in (&entities, &wants_use).join() {
+            let item_equippable = equippable.get(useitem.item);
+            match item_equippable {
+                None => {
+                    let targets = match useitem.target {
+                        None => Targets::Single { target: entity },
+                        Some(target) => {
+                            let tile_idx = map.xy_idx(target.x, target.y) as i32;
+                            match aoe.get(useitem.item) {
+                                None => Targets::Tile { tile_idx },
+                                Some(area) => Targets::Area {
+                                    center: tile_idx,
+                                    radius: area.radius,
+                                },
                             }
                         }
-                    }
+                    };
+                    effects.add_effect(
+                        Some(entity),
+                        EffectType::ItemUse { item: useitem.item },
+                        targets,
+                    );
                 }
-            }
-
-            let item_equippable = equippable.get(useitem.item);
-            match item_equippable {
-                None => {}
                 Some(can_equip) => {
-                    let target_slot = can_equip.slot;
-                    let target = targets[0];
-
-                    let mut to_unequip: Vec<Entity> = Vec::new();
-                    for (item_entity, already_equipped, name) in
-                        (&entities, &equipped, &names).join()
-                    {
-                        if already_equipped.owner == target && already_equipped.slot == target_slot
-                        {
-                            to_unequip.push(item_entity);
-                            if target == *player_entity {
-                                gamelog.entries.push(format!("You unequip {}.", name.name));
-                            }
+                    let target = entity;
+                    let worn: Vec<(Entity, EquipmentSlot, bool)> = (&entities, &equipped)
+                        .join()
+                        .filter(|(_item, worn)| worn.owner == target)
+                        .map(|(item, worn)| (item, worn.slot, two_handed.get(item).is_some()))
+                        .collect();
+                    let (target_slot, to_unequip) = gamesystem::equip_slot(
+                        can_equip.slot,
+                        two_handed.get(useitem.item).is_some(),
+                        melee_weapons.get(useitem.item).is_some(),
+                        &worn,
+                    );
+
+                    // Whatever is stuck to us stays put
+                    let stuck = to_unequip.iter().find(|item| cursed.get(**item).is_some());
+                    if let Some(stuck) = stuck {
+                        if target == *player_entity {
+                            gamelog.entries.push(format!(
+                                "You can't remove the {}; it is cursed!",
+                                names.get(*stuck).unwrap().name
+                            ));
                         }
+                        if let Some(curse) = cursed.get_mut(*stuck) {
+                            curse.known = true;
+                        }
+                        continue;
                     }
+
                     for item in to_unequip.iter() {
+                        if target == *player_entity {
+                            gamelog
+                                .entries
+                                .push(format!("You unequip {}.", names.get(*item).unwrap().name));
+                        }
                         equipped.remove(*item);
                         backpack
                             .insert(*item, InBackpack { owner: target })
@@ -169,154 +231,27 @@ impl<'a> System<'a> for ItemUseSystem {
                         .expect("Unable to inset Equipped component.");
                     backpack.remove(useitem.item);
                     if target == *player_entity {
+                        let off_hand = target_slot == EquipmentSlot::Shield
+                            && melee_weapons.get(useitem.item).is_some();
                         gamelog.entries.push(format!(
-                            "You equip {}.",
-                            names.get(useitem.item).unwrap().name
+                            "You {} {}{}.",
+                            if off_hand { "wield" } else { "equip" },
+                            names.get(useitem.item).unwrap().name,
+                            if off_hand { " in your off hand" } else { "" }
                         ));
-                    }
-                }
-            }
-
-            // Healing potion
-            let item_heals = healing.get(useitem.item);
-            match item_heals {
-                None => {}
-                Some(healer) => {
-                    for target in targets.iter() {
-                        let stats = combat_stats.get_mut(*target);
-                        if let Some(stats) = stats {
-                            stats.hp = i32::min(stats.max_hp, stats.hp + healer.heal_amount);
-                            if entity == *player_entity {
-                                gamelog.entries.push(format!(
-                                    "You use the {}, healing {} hp.",
-                                    names.get(useitem.item).unwrap().name,
-                                    healer.heal_amount
-                                ));
-                            }
-                            used_item = true;
-
-                            let pos = positions.get(*target);
-                            if let Some(pos) = pos {
-                                particle_builder.request(
-                                    pos.x,
-                                    pos.y,
-                                    RGB::named(GREEN),
-                                    RGB::named(BLACK),
-                                    to_cp437('♥'),
-                                    200.0,
-                                );
-                            }
-                        }
-                    }
-                }
-            }
-
-            // Edible
-            let item_edible = provides_food.get(useitem.item);
-            if let Some(_) = item_edible {
-                used_item = true;
-                let target = targets[0];
-                let hc = hunger_clocks.get_mut(target);
-                if let Some(hc) = hc {
-                    hc.state = HungerState::WellFed;
-                    hc.duration = 20;
-                    gamelog.entries.push(format!(
-                        "You eat the {}.",
-                        names.get(useitem.item).unwrap().name
-                    ));
-                }
-            }
-
-            // Dealing damage
-            let item_damages = inflict_damage.get(useitem.item);
-            match item_damages {
-                None => {}
-                Some(damage) => {
-                    used_item = false;
-                    for mob in targets.iter() {
-                        SufferDamage::new_damage(&mut suffer_damage, *mob, damage.damage);
-                        if entity == *player_entity {
-                            let mob_name = names.get(*mob).unwrap();
-                            let item_name = names.get(useitem.item).unwrap();
+                        if let Some(curse) = cursed.get_mut(useitem.item) {
+                            curse.known = true;
                             gamelog.entries.push(format!(
-                                "You use {} on {}, inflicting {} hp.",
-                                item_name.name, mob_name.name, damage.damage
+                                "The {} tightens around you. It is cursed!",
+                                names.get(useitem.item).unwrap().name
                             ));
-
-                            let pos = positions.get(*mob);
-                            if let Some(pos) = pos {
-                                particle_builder.request(
-                                    pos.x,
-                                    pos.y,
-                                    RGB::named(RED),
-                                    RGB::named(BLACK),
-                                    to_cp437('‼'),
-                                    200.0,
-                                )
-                            }
                         }
-
-                        used_item = true;
-                    }
-                }
-            }
-
-            // Confusion scroll
-            let mut add_confusion = Vec::new();
-            {
-                let causes_confusion = confused.get(useitem.item);
-                match causes_confusion {
-                    None => {}
-                    Some(confusion) => {
-                        used_item = false;
-                        for mob in targets.iter() {
-                            add_confusion.push((*mob, confusion.turns));
-                            if entity == *player_entity {
-                                let mob_name = names.get(*mob).unwrap();
-                                let item_name = names.get(useitem.item).unwrap();
-                                gamelog.entries.push(format!(
-                                    "You use {} on {}, confusing them.",
-                                    item_name.name, mob_name.name
-                                ));
-
-                                let pos = positions.get(*mob);
-                                if let Some(pos) = pos {
-                                    particle_builder.request(
-                                        pos.x,
-                                        pos.y,
-                                        RGB::named(MAGENTA),
-                                        RGB::named(BLACK),
-                                        to_cp437('?'),
-                                        200.0,
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            for mob in add_confusion.iter() {
-                confused
-                    .insert(mob.0, Confusion { turns: mob.1 })
-                    .expect("Unable to insert status.");
-            }
-
-            // Scroll of magic mapping
-            let is_mapper = magic_mapper.get(useitem.item);
-            if let Some(_) = is_mapper {
-                used_item = true;
-                gamelog
-                    .entries
-                    .push("The map is revealed to you!".to_string());
-                *runstate = RunState::MagicMapReveal { row: 0 };
-            }
-
-            if used_item {
-                let consumable = consumables.get(useitem.item);
-                match consumable {
-                    None => {}
-                    Some(_) => {
-                        entities.delete(useitem.item).expect("Delete failed");
+                    } else if is_visible(target) {
+                        gamelog.entries.push(format!(
+                            "The {} equips the {}.",
+                            names.get(target).unwrap().name,
+                            names.get(useitem.item).unwrap().name
+                        ));
                     }
                 }
             }
@@ -337,6 +272,7 @@ impl<'a> System<'a> for ItemDropSystem {
         ReadStorage<'a, Name>,
         WriteStorage<'a, Position>,
         WriteStorage<'a, InBackpack>,
+        ReadExpect<'a, Identification>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -348,6 +284,7 @@ impl<'a> System<'a> for ItemDropSystem {
             names,
             mut positions,
             mut backpack,
+            identification,
         ) = data;
 
         for (entity, to_drop) in (&entities, &wants_drop).join() {
@@ -371,7 +308,7 @@ impl<'a> System<'a> for ItemDropSystem {
             if entity == *player_entity {
                 gamelog.entries.push(format!(
                     "You drop the {}.",
-                    names.get(to_drop.item).unwrap().name
+                    identification.display_name(&names.get(to_drop.item).unwrap().name)
                 ));
             }
         }
@@ -391,6 +328,7 @@ impl<'a> System<'a> for ItemRemoveSystem {
         WriteStorage<'a, InBackpack>,
         WriteExpect<'a, gamelog::GameLog>,
         ReadStorage<'a, Name>,
+        WriteStorage<'a, Cursed>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -402,9 +340,20 @@ impl<'a> System<'a> for ItemRemoveSystem {
             mut backpack,
             mut gamelog,
             names,
+            mut cursed,
         ) = data;
 
         for (entity, to_remove) in (&entities, &mut wants_remove).join() {
+            if let Some(curse) = cursed.get_mut(to_remove.item) {
+                curse.known = true;
+                if entity == *player_entity {
+                    gamelog.entries.push(format!(
+                        "You can't remove the {}; it is cursed!",
+                        names.get(to_remove.item).unwrap().name
+                    ));
+                }
+                continue;
+            }
             equipped.remove(to_remove.item);
             backpack
                 .insert(to_remove.item, InBackpack { owner: entity })
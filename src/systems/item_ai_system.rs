@@ -0,0 +1,242 @@
+use super::{
+    gamesystem::equip_slot, Alertness, AreaOfEffect, Charges, CombatStats, Cursed, DamageReduction,
+    DefenseBonus, EquipmentSlot, Equippable, Equipped, HungerClock, InBackpack, InflictsDamage,
+    Item, Map, MeleePowerBonus, MeleeWeapon, Monster, Position, ProvidesFood, ProvidesHealing,
+    Ranged, RunState, StatusEffects, StatusKind, TwoHanded, Viewshed, WantsToPickupItem,
+    WantsToUseItem,
+};
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+/// Decides when monsters pick up, equip and use the items they carry. Runs
+/// before `MonsterAI`, which leaves alone any monster that was given an intent.
+pub struct MonsterItemAI {}
+
+impl<'a> System<'a> for MonsterItemAI {
+    type SystemData = (
+        ReadExpect<'a, Map>,
+        ReadExpect<'a, Point>,
+        ReadExpect<'a, RunState>,
+        Entities<'a>,
+        ReadStorage<'a, Viewshed>,
+        ReadStorage<'a, Monster>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, StatusEffects>,
+        ReadStorage<'a, Alertness>,
+        ReadStorage<'a, CombatStats>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Equippable>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, MeleePowerBonus>,
+        ReadStorage<'a, MeleeWeapon>,
+        ReadStorage<'a, DefenseBonus>,
+        ReadStorage<'a, ProvidesHealing>,
+        ReadStorage<'a, InflictsDamage>,
+        ReadStorage<'a, Ranged>,
+        ReadStorage<'a, AreaOfEffect>,
+        ReadStorage<'a, TwoHanded>,
+        ReadStorage<'a, DamageReduction>,
+        (
+            ReadStorage<'a, Cursed>,
+            ReadStorage<'a, Charges>,
+            ReadStorage<'a, HungerClock>,
+            ReadStorage<'a, ProvidesFood>,
+        ),
+        WriteStorage<'a, WantsToUseItem>,
+        WriteStorage<'a, WantsToPickupItem>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            map,
+            player_pos,
+            runstate,
+            entities,
+            viewshed,
+            monster,
+            position,
+            statuses,
+            alertness,
+            combat_stats,
+            items,
+            backpack,
+            equippable,
+            equipped,
+            melee_power_bonuses,
+            melee_weapons,
+            defense_bonuses,
+            healing,
+            inflict_damage,
+            ranged,
+            aoe,
+            two_handed,
+            reductions,
+            (cursed, charges, hunger_clocks, food),
+            mut wants_use,
+            mut wants_pickup,
+        ) = data;
+
+        if *runstate != RunState::MonsterTurn {
+            return;
+        }
+
+        for (entity, viewshed, _monster, pos, _alertness) in
+            (&entities, &viewshed, &monster, &position, !&alertness).join()
+        {
+            if statuses
+                .get(entity)
+                .is_some_and(|s| s.loses_turn() || s.has(StatusKind::Confused))
+            {
+                continue;
+            }
+
+            let distance =
+                DistanceAlg::Pythagoras.distance2d(Point::new(pos.x, pos.y), *player_pos);
+            let can_see_player = viewshed.visible_tiles.contains(&*player_pos);
+            let carried: Vec<Entity> = (&entities, &backpack)
+                .join()
+                .filter(|item| item.1.owner == entity)
+                .map(|item| item.0)
+                .collect();
+
+            // Drink a healing potion when badly hurt
+            let mut item_to_use: Option<(Entity, Option<Point>)> = None;
+            if let Some(stats) = combat_stats.get(entity) {
+                if stats.hp < stats.max_hp / 2 {
+                    if let Some(potion) = carried.iter().find(|item| healing.get(**item).is_some())
+                    {
+                        item_to_use = Some((*potion, None));
+                    }
+                }
+            }
+
+            // Eat something when hungry
+            if item_to_use.is_none() && hunger_clocks.get(entity).is_some_and(|hc| hc.hungry()) {
+                if let Some(meal) = carried.iter().find(|item| food.get(**item).is_some()) {
+                    item_to_use = Some((*meal, None));
+                }
+            }
+
+            // Put on anything that beats whatever it would displace
+            if item_to_use.is_none() {
+                let worn: Vec<(Entity, EquipmentSlot, bool)> = (&entities, &equipped)
+                    .join()
+                    .filter(|(_item, worn)| worn.owner == entity)
+                    .map(|(item, worn)| (item, worn.slot, two_handed.get(item).is_some()))
+                    .collect();
+                for item in carried.iter() {
+                    if let Some(can_equip) = equippable.get(*item) {
+                        let (_slot, displaced) = equip_slot(
+                            can_equip.slot,
+                            two_handed.get(*item).is_some(),
+                            melee_weapons.get(*item).is_some(),
+                            &worn,
+                        );
+                        if displaced.iter().any(|e| cursed.get(*e).is_some()) {
+                            continue;
+                        }
+                        let current_best = if displaced.is_empty() {
+                            None
+                        } else {
+                            Some(
+                                displaced
+                                    .iter()
+                                    .map(|e| {
+                                        equipment_bonus(
+                                            *e,
+                                            &melee_power_bonuses,
+                                            &melee_weapons,
+                                            &defense_bonuses,
+                                            &reductions,
+                                        )
+                                    })
+                                    .sum::<i32>(),
+                            )
+                        };
+                        let bonus = equipment_bonus(
+                            *item,
+                            &melee_power_bonuses,
+                            &melee_weapons,
+                            &defense_bonuses,
+                            &reductions,
+                        );
+                        if current_best.is_none_or(|best| bonus > best) {
+                            item_to_use = Some((*item, None));
+                            break;
+                        }
+                    }
+                }
+            }
+
+            // Read an offensive scroll or zap a wand at the player, if it won't hit us as well
+            if item_to_use.is_none() && can_see_player && distance >= 1.5 {
+                for item in carried.iter() {
+                    if charges.get(*item).is_some_and(|c| c.uses < 1) {
+                        continue;
+                    }
+                    if let (Some(range), Some(_)) = (ranged.get(*item), inflict_damage.get(*item)) {
+                        let safe = match aoe.get(*item) {
+                            None => true,
+                            Some(area) => distance > area.radius as f32,
+                        };
+                        if safe && distance <= range.range as f32 {
+                            item_to_use = Some((*item, Some(*player_pos)));
+                            break;
+                        }
+                    }
+                }
+            }
+
+            if let Some((item, target)) = item_to_use {
+                wants_use
+                    .insert(entity, WantsToUseItem { item, target })
+                    .expect("Unable to insert monster item use");
+                continue;
+            }
+
+            // Pick up whatever we're standing on, unless there's fighting to do
+            if !can_see_player {
+                let my_idx = map.xy_idx(pos.x, pos.y);
+                let item_here = map.tile_content[my_idx]
+                    .iter()
+                    .find(|e| items.get(**e).is_some());
+                if let Some(item) = item_here {
+                    wants_pickup
+                        .insert(
+                            entity,
+                            WantsToPickupItem {
+                                collected_by: entity,
+                                item: *item,
+                            },
+                        )
+                        .expect("Unable to insert monster pickup");
+                }
+            }
+        }
+    }
+}
+
+fn equipment_bonus(
+    item: Entity,
+    melee_power_bonuses: &ReadStorage<MeleePowerBonus>,
+    melee_weapons: &ReadStorage<MeleeWeapon>,
+    defense_bonuses: &ReadStorage<DefenseBonus>,
+    reductions: &ReadStorage<DamageReduction>,
+) -> i32 {
+    let mut bonus = 0;
+    if let Some(power) = melee_power_bonuses.get(item) {
+        bonus += power.power;
+    }
+    if let Some(weapon) = melee_weapons.get(item) {
+        // Judge a weapon by its average hit
+        bonus += weapon.damage_n_dice * (weapon.damage_die_type + 1) / 2 + weapon.damage_bonus;
+    }
+    if let Some(defense) = defense_bonuses.get(item) {
+        bonus += defense.defense;
+    }
+    if let Some(reduction) = reductions.get(item) {
+        bonus += reduction.amount;
+    }
+    bonus
+}
@@ -1,6 +1,12 @@
 use super::{
-    gamelog::GameLog, CombatStats, DefenseBonus, Equipped, HungerClock, HungerState,
-    MeleePowerBonus, Name, ParticleBuilder, Position, SufferDamage, WantsToMelee,
+    gamelog::GameLog,
+    gamesystem::{
+        armor_class, parse_dice_string, skill_level, try_block, OFF_HAND_PENALTY, UNARMED_DAMAGE,
+    },
+    AlertState, Alertness, Attributes, BlockChance, CombatStats, DamageType, DefenseBonus,
+    EffectQueue, EffectType, EquipmentSlot, Equipped, HungerClock, HungerState, InflictsDamage,
+    MeleePowerBonus, MeleeWeapon, Name, NoiseBuilder, Position, Skill, Skills, Targets,
+    WantsToMelee,
 };
 use bracket_lib::prelude::*;
 use specs::prelude::*;
@@ -14,13 +20,20 @@ impl<'a> System<'a> for MeleeCombatSystem {
         WriteStorage<'a, WantsToMelee>,
         ReadStorage<'a, Name>,
         ReadStorage<'a, CombatStats>,
-        WriteStorage<'a, SufferDamage>,
+        WriteExpect<'a, EffectQueue>,
         ReadStorage<'a, MeleePowerBonus>,
         ReadStorage<'a, DefenseBonus>,
         ReadStorage<'a, Equipped>,
-        WriteExpect<'a, ParticleBuilder>,
         ReadStorage<'a, Position>,
         ReadStorage<'a, HungerClock>,
+        ReadStorage<'a, Alertness>,
+        WriteExpect<'a, NoiseBuilder>,
+        ReadStorage<'a, Attributes>,
+        ReadStorage<'a, Skills>,
+        ReadStorage<'a, MeleeWeapon>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        ReadStorage<'a, BlockChance>,
+        ReadStorage<'a, InflictsDamage>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -30,80 +43,226 @@ impl<'a> System<'a> for MeleeCombatSystem {
             mut wants_melee,
             names,
             combat_stats,
-            mut inflict_damage,
+            mut effects,
             melee_power_bonuses,
             defense_bonuses,
             equipped,
-            mut particle_builder,
             positions,
             hunger_clocks,
+            alertness,
+            mut noise_builder,
+            attributes,
+            skills,
+            melee_weapons,
+            mut rng,
+            block_chances,
+            inflicts_damage,
         ) = data;
 
         for (entity, wants_melee, name, stats) in
             (&entities, &mut wants_melee, &names, &combat_stats).join()
         {
-            if stats.hp > 0 {
-                let mut offensive_bonus = 0;
-                for (_item_entity, power_bonus, equipped_by) in
-                    (&entities, &melee_power_bonuses, &equipped).join()
-                {
-                    if equipped_by.owner == entity {
-                        offensive_bonus += power_bonus.power;
-                    }
+            if stats.hp < 1 {
+                continue;
+            }
+            let target_stats = combat_stats.get(wants_melee.target).unwrap();
+            if target_stats.hp < 1 {
+                continue;
+            }
+            let target_name = names.get(wants_melee.target).unwrap();
+
+            let mut hit_bonus = skill_level(skills.get(entity), Skill::Melee);
+            let mut damage_bonus = 0;
+            if let Some(attrs) = attributes.get(entity) {
+                hit_bonus += attrs.might.bonus;
+                damage_bonus += attrs.might.bonus;
+            }
+            for (_item_entity, power_bonus, equipped_by) in
+                (&entities, &melee_power_bonuses, &equipped).join()
+            {
+                if equipped_by.owner == entity {
+                    damage_bonus += power_bonus.power;
                 }
+            }
+            let hc = hunger_clocks.get(entity);
+            match hc.map(|hc| hc.state) {
+                Some(HungerState::WellFed) => hit_bonus += 1,
+                Some(HungerState::Gorged) => hit_bonus -= 1,
+                _ => {}
+            }
 
-                let hc = hunger_clocks.get(entity);
-                if let Some(hc) = hc {
-                    if hc.state == HungerState::WellFed {
-                        offensive_bonus += 1;
+            // One swing with the main hand (or a fist), and another with a
+            // weapon held in the off hand
+            let (n_dice, die_type, unarmed_bonus) = parse_dice_string(UNARMED_DAMAGE);
+            let mut attacks: Vec<(i32, i32, i32, i32, Option<Entity>)> = Vec::new();
+            let mut off_hand: Option<(i32, i32, i32, i32, Option<Entity>)> = None;
+            for (weapon_entity, weapon, equipped_by) in
+                (&entities, &melee_weapons, &equipped).join()
+            {
+                if equipped_by.owner != entity {
+                    continue;
+                }
+                let attack = (
+                    weapon.damage_n_dice,
+                    weapon.damage_die_type,
+                    weapon.damage_bonus,
+                    weapon.hit_bonus,
+                    Some(weapon_entity),
+                );
+                match equipped_by.slot {
+                    EquipmentSlot::Melee => attacks.push(attack),
+                    EquipmentSlot::Shield => {
+                        off_hand = Some((
+                            attack.0,
+                            attack.1,
+                            attack.2,
+                            attack.3 - OFF_HAND_PENALTY,
+                            attack.4,
+                        ))
                     }
+                    _ => {}
                 }
+            }
+            if attacks.is_empty() {
+                attacks.push((n_dice, die_type, unarmed_bonus, 0, None));
+            }
+            attacks.extend(off_hand);
 
-                let target_stats = combat_stats.get(wants_melee.target).unwrap();
-                if target_stats.hp > 0 {
-                    let target_name = names.get(wants_melee.target).unwrap();
-
-                    let mut defensive_bonus = 0;
-                    for (_item_entity, defense_bonus, equipped_by) in
-                        (&entities, &defense_bonuses, &equipped).join()
-                    {
-                        if equipped_by.owner == wants_melee.target {
-                            defensive_bonus += defense_bonus.defense;
-                        }
-                    }
+            let armor_class = armor_class(
+                wants_melee.target,
+                &skills,
+                &attributes,
+                &defense_bonuses,
+                &equipped,
+            );
 
-                    let pos = positions.get(wants_melee.target);
-                    if let Some(pos) = pos {
-                        particle_builder.request(
-                            pos.x,
-                            pos.y,
-                            RGB::named(ORANGE),
-                            RGB::named(BLACK),
-                            to_cp437('‼'),
-                            200.0,
-                        );
-                    }
+            if let Some(pos) = positions.get(entity) {
+                noise_builder.request(pos.x, pos.y, 8);
+            }
 
-                    let damage = i32::max(
-                        0,
-                        (stats.power + offensive_bonus) - (target_stats.defense + defensive_bonus),
-                    );
-                    if damage == 0 {
-                        log.entries.push(format!(
-                            "{} is unable to hurt {}.",
-                            &name.name, &target_name.name
-                        ));
+            // A target that doesn't see it coming can't get out of the way, and a
+            // sleeping one takes the blow as squarely as a critical hit
+            let sneak_attack = alertness.get(wants_melee.target);
+
+            for (n_dice, die_type, weapon_damage, weapon_hit, weapon) in attacks {
+                let natural_roll = rng.roll_dice(1, 20);
+                let critical = natural_roll == 20
+                    || sneak_attack.is_some_and(|s| s.state == AlertState::Asleep);
+                let hit = sneak_attack.is_some()
+                    || critical
+                    || (natural_roll != 1 && natural_roll + hit_bonus + weapon_hit >= armor_class);
+
+                if !hit {
+                    if natural_roll == 1 {
+                        log.entries
+                            .push(format!("{} fumbles the attack!", &name.name));
                     } else {
+                        log.entries
+                            .push(format!("{} misses {}.", &name.name, &target_name.name));
+                    }
+                    effects.add_effect(
+                        Some(entity),
+                        hit_particle(RGB::named(CYAN)),
+                        Targets::Single {
+                            target: wants_melee.target,
+                        },
+                    );
+                    continue;
+                }
+
+                // A raised shield can turn aside even a good blow, if it's seen coming
+                if sneak_attack.is_none() {
+                    if let Some(shield) = try_block(
+                        wants_melee.target,
+                        &entities,
+                        &block_chances,
+                        &equipped,
+                        &mut rng,
+                    ) {
                         log.entries.push(format!(
-                            "{} hits {}, for {} hp.",
-                            &name.name, &target_name.name, damage
+                            "{} blocks the blow with the {}.",
+                            &target_name.name,
+                            &names.get(shield).unwrap().name
                         ));
-                        SufferDamage::new_damage(&mut inflict_damage, wants_melee.target, damage);
+                        effects.add_effect(
+                            Some(entity),
+                            hit_particle(RGB::named(LIGHT_BLUE)),
+                            Targets::Single {
+                                target: wants_melee.target,
+                            },
+                        );
+                        continue;
                     }
                 }
+
+                let mut damage = rng.roll_dice(n_dice, die_type) + damage_bonus + weapon_damage;
+                if critical {
+                    damage += rng.roll_dice(n_dice, die_type);
+                }
+                let damage = i32::max(1, damage);
+
+                effects.add_effect(
+                    Some(entity),
+                    hit_particle(RGB::named(ORANGE)),
+                    Targets::Single {
+                        target: wants_melee.target,
+                    },
+                );
+
+                if sneak_attack.is_some() {
+                    log.entries.push(format!(
+                        "{} sneak attacks {}, for {} hp.",
+                        &name.name, &target_name.name, damage
+                    ));
+                } else if critical {
+                    log.entries.push(format!(
+                        "{} critically hits {}, for {} hp!",
+                        &name.name, &target_name.name, damage
+                    ));
+                } else {
+                    log.entries.push(format!(
+                        "{} hits {}, for {} hp.",
+                        &name.name, &target_name.name, damage
+                    ));
+                }
+                effects.add_effect(
+                    Some(entity),
+                    EffectType::Damage {
+                        amount: damage,
+                        damage_type: DamageType::Physical,
+                        weapon: true,
+                    },
+                    Targets::Single {
+                        target: wants_melee.target,
+                    },
+                );
+
+                // Enchanted weapons bite with their element as well
+                if let Some(extra) = weapon.and_then(|w| inflicts_damage.get(w)) {
+                    effects.add_effect(
+                        Some(entity),
+                        EffectType::Damage {
+                            amount: extra.damage,
+                            damage_type: extra.damage_type,
+                            weapon: false,
+                        },
+                        Targets::Single {
+                            target: wants_melee.target,
+                        },
+                    );
+                }
             }
         }
 
         wants_melee.clear()
     }
 }
+
+fn hit_particle(fg: RGB) -> EffectType {
+    EffectType::Particle {
+        glyph: to_cp437('‼'),
+        fg,
+        bg: RGB::named(BLACK),
+        lifetime: 200.0,
+    }
+}
@@ -1,9 +1,11 @@
 use super::{
-    Confusion, EntityMoved, Map, Monster, ParticleBuilder, Position, RunState, Viewshed,
-    WantsToMelee,
+    gamelog::GameLog, Alertness, Ally, CombatStats, EntityMoved, EntryTrigger, HungerClock, Item,
+    Map, Monster, Name, PackMember, Position, ProvidesFood, RunState, StatusEffects, StatusKind,
+    Tracker, Viewshed, WantsToMelee, WantsToPickupItem, WantsToUseItem,
 };
 use bracket_lib::prelude::*;
 use specs::prelude::*;
+use std::collections::HashSet;
 
 pub struct MonsterAI {}
 
@@ -18,9 +20,21 @@ impl<'a> System<'a> for MonsterAI {
         ReadStorage<'a, Monster>,
         WriteStorage<'a, Position>,
         WriteStorage<'a, WantsToMelee>,
-        WriteStorage<'a, Confusion>,
-        WriteExpect<'a, ParticleBuilder>,
+        ReadStorage<'a, StatusEffects>,
         WriteStorage<'a, EntityMoved>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, WantsToUseItem>,
+        ReadStorage<'a, WantsToPickupItem>,
+        WriteStorage<'a, Alertness>,
+        ReadStorage<'a, PackMember>,
+        ReadStorage<'a, CombatStats>,
+        WriteExpect<'a, GameLog>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Ally>,
+        ReadStorage<'a, Tracker>,
+        ReadStorage<'a, HungerClock>,
+        ReadStorage<'a, ProvidesFood>,
+        ReadStorage<'a, EntryTrigger>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -34,41 +48,109 @@ impl<'a> System<'a> for MonsterAI {
             monster,
             mut position,
             mut wants_to_melee,
-            mut confused,
-            mut particle_builder,
+            statuses,
             mut entity_moved,
+            items,
+            wants_use,
+            wants_pickup,
+            mut alertness,
+            packs,
+            combat_stats,
+            mut log,
+            names,
+            allies,
+            trackers,
+            hunger_clocks,
+            food,
+            entry_triggers,
         ) = data;
 
         if *runstate != RunState::MonsterTurn {
             return;
         }
 
-        for (entity, mut viewshed, _monster, mut pos) in
+        // A pack hunts together as soon as any awake member sees the player
+        let mut hunting_packs: HashSet<usize> = HashSet::new();
+        for (viewshed, pack, _alertness) in (&viewshed, &packs, !&alertness).join() {
+            if viewshed.visible_tiles.contains(&*player_pos) {
+                hunting_packs.insert(pack.pack);
+            }
+        }
+        let mut roused: Vec<Entity> = Vec::new();
+        for (entity, pack, _alertness) in (&entities, &packs, &alertness).join() {
+            if hunting_packs.contains(&pack.pack) {
+                roused.push(entity);
+            }
+        }
+        for entity in roused.iter() {
+            alertness.remove(*entity);
+            if let (Some(pos), Some(name)) = (position.get(*entity), names.get(*entity)) {
+                if map.visible_tiles[map.xy_idx(pos.x, pos.y)] {
+                    log.entries
+                        .push(format!("The {} is roused by its pack!", &name.name));
+                }
+            }
+        }
+
+        // Packs with at least one healthy member can let the wounded fall back
+        let mut healthy_packs: HashSet<usize> = HashSet::new();
+        for (pack, stats) in (&packs, &combat_stats).join() {
+            if !is_wounded(stats) {
+                healthy_packs.insert(pack.pack);
+            }
+        }
+
+        for (entity, viewshed, _monster, pos) in
             (&entities, &mut viewshed, &monster, &mut position).join()
         {
             let mut can_act = true;
 
-            let is_confused = confused.get_mut(entity);
-            if let Some(i_am_confused) = is_confused {
-                i_am_confused.turns -= 1;
-                if i_am_confused.turns < 1 {
-                    confused.remove(entity);
-                }
+            let status = statuses.get(entity);
+            if status.is_some_and(|s| s.loses_turn() || s.has(StatusKind::Confused)) {
+                can_act = false;
+            }
+
+            // Sleeping or unaware monsters wait until noise or the player rouses them
+            if alertness.get(entity).is_some() {
+                can_act = false;
+            }
+
+            // Already busy with an item this turn
+            if wants_use.get(entity).is_some() || wants_pickup.get(entity).is_some() {
                 can_act = false;
-                particle_builder.request(
-                    pos.x,
-                    pos.y,
-                    RGB::named(MAGENTA),
-                    RGB::named(BLACK),
-                    to_cp437('?'),
-                    200.0,
-                )
             }
 
-            if can_act {
+            if !can_act {
+                continue;
+            }
+
+            // Haste grants a second full action, so a monster can close in and
+            // still strike in the same turn
+            let actions = if status.is_some_and(|s| s.extra_turn()) {
+                2
+            } else {
+                1
+            };
+            for _action in 0..actions {
                 let distance =
                     DistanceAlg::Pythagoras.distance2d(Point::new(pos.x, pos.y), *player_pos);
-                if distance < 1.5 {
+                let pack = packs.get(entity).map(|p| p.pack);
+                let hunting = viewshed.visible_tiles.contains(&*player_pos)
+                    || pack.is_some_and(|p| hunting_packs.contains(&p));
+                let falling_back = pack.is_some_and(|p| healthy_packs.contains(&p))
+                    && combat_stats.get(entity).is_some_and(is_wounded);
+                let hungry = hunger_clocks.get(entity).is_some_and(|hc| hc.hungry());
+
+                let retreat = if falling_back && hunting {
+                    retreat_tile(&map, Point::new(pos.x, pos.y), *player_pos)
+                } else {
+                    None
+                };
+
+                let move_target: Option<usize>;
+                if retreat.is_some() {
+                    move_target = retreat;
+                } else if distance < 1.5 {
                     wants_to_melee
                         .insert(
                             entity,
@@ -77,26 +159,188 @@ impl<'a> System<'a> for MonsterAI {
                             },
                         )
                         .expect("Unable to insert monster attack");
-                } else if viewshed.visible_tiles.contains(&*player_pos) {
-                    let path = a_star_search(
-                        map.xy_idx(pos.x, pos.y) as i32,
-                        map.xy_idx(player_pos.x, player_pos.y) as i32,
-                        &*map,
-                    );
-                    if path.success && path.steps.len() > 1 {
-                        let mut idx = map.xy_idx(pos.x, pos.y);
-                        map.blocked[idx] = false;
-                        pos.x = path.steps[1] as i32 % map.width;
-                        pos.y = path.steps[1] as i32 / map.width;
-                        idx = map.xy_idx(pos.x, pos.y);
-                        map.blocked[idx] = true;
-                        viewshed.dirty = true;
-                        entity_moved
-                            .insert(entity, EntityMoved {})
-                            .expect("Failed to insert marker.");
+                    break;
+                } else if let Some(ally) = adjacent_ally(&map, Point::new(pos.x, pos.y), &allies) {
+                    wants_to_melee
+                        .insert(entity, WantsToMelee { target: ally })
+                        .expect("Unable to insert monster attack");
+                    break;
+                } else if hunting {
+                    let player_idx = map.xy_idx(player_pos.x, player_pos.y);
+                    if pack.is_some() {
+                        // Spread out around the player rather than queueing up behind each other
+                        move_target = flanking_tile(&map, Point::new(pos.x, pos.y), *player_pos)
+                            .or(Some(player_idx));
+                    } else {
+                        move_target = Some(player_idx);
                     }
+                } else if let Some(meal) = hungry
+                    .then(|| {
+                        closest_visible(&map, Point::new(pos.x, pos.y), viewshed, |e| {
+                            food.get(e).is_some()
+                        })
+                    })
+                    .flatten()
+                {
+                    // An empty stomach beats following a trail
+                    move_target = Some(meal);
+                } else if let Some(trail) = trackers
+                    .get(entity)
+                    .and_then(|_| scent_step(&map, Point::new(pos.x, pos.y)))
+                {
+                    move_target = Some(trail);
+                } else {
+                    move_target = closest_visible(&map, Point::new(pos.x, pos.y), viewshed, |e| {
+                        items.get(e).is_some()
+                    });
                 }
+
+                let Some(target_idx) = move_target else {
+                    break;
+                };
+                let path = a_star_search(map.xy_idx(pos.x, pos.y) as i32, target_idx as i32, &*map);
+                if !path.success || path.steps.len() < 2 {
+                    break;
+                }
+                let step = path.steps[1];
+                let mut idx = map.xy_idx(pos.x, pos.y);
+                map.blocked[idx] = false;
+                pos.x = step as i32 % map.width;
+                pos.y = step as i32 / map.width;
+                idx = map.xy_idx(pos.x, pos.y);
+                map.blocked[idx] = true;
+                viewshed.dirty = true;
+                entity_moved
+                    .insert(entity, EntityMoved {})
+                    .expect("Failed to insert marker.");
+
+                // Stepping onto a trap springs it and ends the move
+                if map.tile_content[idx]
+                    .iter()
+                    .any(|e| entry_triggers.get(*e).is_some())
+                {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// The nearest tile we can see holding something `wanted`.
+fn closest_visible(
+    map: &Map,
+    from: Point,
+    viewshed: &Viewshed,
+    wanted: impl Fn(Entity) -> bool,
+) -> Option<usize> {
+    let mut closest: Option<(usize, f32)> = None;
+    for tile in viewshed.visible_tiles.iter() {
+        let idx = map.xy_idx(tile.x, tile.y);
+        if map.tile_content[idx].iter().any(|e| wanted(*e)) {
+            let distance = DistanceAlg::Pythagoras.distance2d(from, *tile);
+            if closest.is_none_or(|c| distance < c.1) {
+                closest = Some((idx, distance));
+            }
+        }
+    }
+    closest.map(|c| c.0)
+}
+
+fn is_wounded(stats: &CombatStats) -> bool {
+    stats.hp * 3 < stats.max_hp
+}
+
+/// The free tile next to the player that is closest to us, so that pack
+/// members converge on different sides.
+fn flanking_tile(map: &Map, from: Point, player: Point) -> Option<usize> {
+    let mut best: Option<(usize, f32)> = None;
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            let tile = Point::new(player.x + dx, player.y + dy);
+            if (dx == 0 && dy == 0) || !in_bounds(map, tile) {
+                continue;
+            }
+            let idx = map.xy_idx(tile.x, tile.y);
+            if map.blocked[idx] {
+                continue;
+            }
+            let distance = DistanceAlg::Pythagoras.distance2d(from, tile);
+            if best.is_none_or(|b| distance < b.1) {
+                best = Some((idx, distance));
+            }
+        }
+    }
+    best.map(|b| b.0)
+}
+
+/// The free tile next to us that takes us furthest from the player, if any
+/// of them is further than where we stand now.
+fn retreat_tile(map: &Map, from: Point, player: Point) -> Option<usize> {
+    let mut best: Option<(usize, f32)> = None;
+    let current = DistanceAlg::Pythagoras.distance2d(from, player);
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            let tile = Point::new(from.x + dx, from.y + dy);
+            if (dx == 0 && dy == 0) || !in_bounds(map, tile) {
+                continue;
+            }
+            let idx = map.xy_idx(tile.x, tile.y);
+            if map.blocked[idx] {
+                continue;
+            }
+            let distance = DistanceAlg::Pythagoras.distance2d(tile, player);
+            if distance > current && best.is_none_or(|b| distance > b.1) {
+                best = Some((idx, distance));
+            }
+        }
+    }
+    best.map(|b| b.0)
+}
+
+/// The free neighbouring tile with the freshest scent, if the trail leads
+/// anywhere from here.
+fn scent_step(map: &Map, from: Point) -> Option<usize> {
+    let mut best: Option<(usize, i32)> = None;
+    let current = map.scent[map.xy_idx(from.x, from.y)];
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            let tile = Point::new(from.x + dx, from.y + dy);
+            if (dx == 0 && dy == 0) || !in_bounds(map, tile) {
+                continue;
+            }
+            let idx = map.xy_idx(tile.x, tile.y);
+            if map.blocked[idx] {
+                continue;
+            }
+            let scent = map.scent[idx];
+            if scent > current && best.is_none_or(|b| scent > b.1) {
+                best = Some((idx, scent));
             }
         }
     }
+    best.map(|b| b.0)
+}
+
+/// Any of the player's allies standing next to us.
+fn adjacent_ally(map: &Map, from: Point, allies: &ReadStorage<Ally>) -> Option<Entity> {
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            let tile = Point::new(from.x + dx, from.y + dy);
+            if (dx == 0 && dy == 0) || !in_bounds(map, tile) {
+                continue;
+            }
+            let idx = map.xy_idx(tile.x, tile.y);
+            if let Some(ally) = map.tile_content[idx]
+                .iter()
+                .find(|e| allies.get(**e).is_some())
+            {
+                return Some(*ally);
+            }
+        }
+    }
+    None
+}
+
+fn in_bounds(map: &Map, tile: Point) -> bool {
+    tile.x > 0 && tile.x < map.width - 1 && tile.y > 0 && tile.y < map.height - 1
 }
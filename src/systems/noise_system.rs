@@ -0,0 +1,127 @@
+use super::{gamelog::GameLog, AlertState, Alertness, Map, Name, Position, TileType};
+use bracket_lib::prelude::RandomNumberGenerator;
+use specs::prelude::*;
+use std::collections::VecDeque;
+
+struct NoiseRequest {
+    x: i32,
+    y: i32,
+    volume: i32,
+}
+
+pub struct NoiseBuilder {
+    requests: Vec<NoiseRequest>,
+}
+
+impl Default for NoiseBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NoiseBuilder {
+    pub fn new() -> Self {
+        NoiseBuilder {
+            requests: Vec::new(),
+        }
+    }
+
+    pub fn request(&mut self, x: i32, y: i32, volume: i32) {
+        self.requests.push(NoiseRequest { x, y, volume })
+    }
+}
+
+pub struct NoiseSystem {}
+
+impl<'a> System<'a> for NoiseSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Map>,
+        WriteExpect<'a, NoiseBuilder>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        WriteExpect<'a, GameLog>,
+        WriteStorage<'a, Alertness>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Name>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, map, mut noise_builder, mut rng, mut log, mut alertness, positions, names) =
+            data;
+
+        let mut woken: Vec<Entity> = Vec::new();
+        for noise in noise_builder.requests.iter() {
+            let distances = noise_distances(&map, noise.x, noise.y, noise.volume);
+            for (entity, alert, pos) in (&entities, &alertness, &positions).join() {
+                let distance = distances[map.xy_idx(pos.x, pos.y)];
+                if distance > noise.volume {
+                    continue;
+                }
+
+                // Sleepers need a louder (or closer) noise than the merely unaware
+                let needed = match alert.state {
+                    AlertState::Asleep => distance * 2,
+                    AlertState::Unaware => distance,
+                };
+                if rng.roll_dice(1, noise.volume) > needed && !woken.contains(&entity) {
+                    woken.push(entity);
+                }
+            }
+        }
+
+        for entity in woken.iter() {
+            if let (Some(alert), Some(pos)) = (alertness.get(*entity), positions.get(*entity)) {
+                if map.visible_tiles[map.xy_idx(pos.x, pos.y)] {
+                    if let Some(name) = names.get(*entity) {
+                        let message = match alert.state {
+                            AlertState::Asleep => format!("The {} wakes up!", name.name),
+                            AlertState::Unaware => format!("The {} hears you!", name.name),
+                        };
+                        log.entries.push(message);
+                    }
+                }
+            }
+            alertness.remove(*entity);
+        }
+
+        noise_builder.requests.clear();
+    }
+}
+
+/// Walking distance from the noise source to every tile it can reach, with
+/// walls soaking up the sound. Tiles beyond `volume` are left at `i32::MAX`.
+fn noise_distances(map: &Map, x: i32, y: i32, volume: i32) -> Vec<i32> {
+    let mut distances = vec![i32::MAX; map.tiles.len()];
+    let mut open_list: VecDeque<(i32, i32)> = VecDeque::new();
+
+    distances[map.xy_idx(x, y)] = 0;
+    open_list.push_back((x, y));
+    while let Some((cx, cy)) = open_list.pop_front() {
+        let distance = distances[map.xy_idx(cx, cy)] + 1;
+        if distance > volume {
+            continue;
+        }
+        for (dx, dy) in [
+            (-1, 0),
+            (1, 0),
+            (0, -1),
+            (0, 1),
+            (-1, -1),
+            (1, -1),
+            (-1, 1),
+            (1, 1),
+        ] {
+            let (nx, ny) = (cx + dx, cy + dy);
+            if nx < 0 || nx >= map.width || ny < 0 || ny >= map.height {
+                continue;
+            }
+            let idx = map.xy_idx(nx, ny);
+            if map.tiles[idx] != TileType::Wall && distances[idx] > distance {
+                distances[idx] = distance;
+                open_list.push_back((nx, ny));
+            }
+        }
+    }
+
+    distances
+}
@@ -8,7 +8,7 @@ pub fn cull_dead_particles(ecs: &mut World, ctx: &BTerm) {
     {
         let mut particles = ecs.write_storage::<ParticleLifetime>();
         let entities = ecs.entities();
-        for (entity, mut particle) in (&entities, &mut particles).join() {
+        for (entity, particle) in (&entities, &mut particles).join() {
             particle.lifetime_ms -= ctx.frame_time_ms;
             if particle.lifetime_ms < 0.0 {
                 dead_particles.push(entity);
@@ -35,6 +35,12 @@ pub struct ParticleBuilder {
     requests: Vec<ParticleRequest>,
 }
 
+impl Default for ParticleBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ParticleBuilder {
     pub fn new() -> Self {
         ParticleBuilder {
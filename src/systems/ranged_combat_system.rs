@@ -0,0 +1,251 @@
+use super::{
+    gamelog::GameLog,
+    gamesystem::{armor_class, skill_level, try_block},
+    Ammunition, Attributes, BlockChance, CombatStats, DamageType, DefenseBonus, EffectQueue,
+    EffectType, Equipped, InBackpack, InflictsDamage, Map, Name, NoiseBuilder, ParticleBuilder,
+    Position, RangedWeapon, Skill, Skills, Targets, TileType, WantsToShoot,
+};
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+/// Looses ammunition from an equipped ranged weapon along a straight line
+/// towards the target, stopping at the first wall or creature in the way.
+pub struct RangedCombatSystem {}
+
+impl<'a> System<'a> for RangedCombatSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Map>,
+        WriteExpect<'a, GameLog>,
+        WriteStorage<'a, WantsToShoot>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, CombatStats>,
+        ReadStorage<'a, RangedWeapon>,
+        ReadStorage<'a, Ammunition>,
+        WriteStorage<'a, InBackpack>,
+        WriteStorage<'a, Position>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, Attributes>,
+        ReadStorage<'a, Skills>,
+        ReadStorage<'a, DefenseBonus>,
+        WriteExpect<'a, ParticleBuilder>,
+        WriteExpect<'a, EffectQueue>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        ReadStorage<'a, BlockChance>,
+        ReadStorage<'a, InflictsDamage>,
+        WriteExpect<'a, NoiseBuilder>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            map,
+            mut log,
+            mut wants_shoot,
+            names,
+            combat_stats,
+            ranged_weapons,
+            ammunition,
+            mut backpack,
+            mut positions,
+            equipped,
+            attributes,
+            skills,
+            defense_bonuses,
+            mut particle_builder,
+            mut effects,
+            mut rng,
+            block_chances,
+            inflicts_damage,
+            mut noise_builder,
+        ) = data;
+
+        for (entity, wants_shoot, name) in (&entities, &wants_shoot, &names).join() {
+            let Some((weapon_entity, weapon)) = (&entities, &ranged_weapons, &equipped)
+                .join()
+                .find(|(_entity, _weapon, equipped_by)| equipped_by.owner == entity)
+                .map(|(weapon_entity, weapon, _equipped_by)| (weapon_entity, weapon))
+            else {
+                continue;
+            };
+            let ammo = wants_shoot.ammo;
+            let loaded = ammunition
+                .get(ammo)
+                .is_some_and(|a| a.ammo_type == weapon.ammo)
+                && backpack.get(ammo).is_some_and(|c| c.owner == entity);
+            if !loaded {
+                log.entries
+                    .push(format!("{} has nothing to shoot.", &name.name));
+                continue;
+            }
+            let Some(start) = positions.get(entity).map(|pos| Point::new(pos.x, pos.y)) else {
+                continue;
+            };
+
+            noise_builder.request(start.x, start.y, 8);
+
+            let (flight, victim) =
+                projectile_path(&map, &combat_stats, entity, start, wants_shoot.target);
+            animate_projectile(&mut particle_builder, &flight, start, wants_shoot.target);
+
+            if let Some(victim) = victim {
+                let target_name = names.get(victim).unwrap();
+                let natural_roll = rng.roll_dice(1, 20);
+                let mut hit_bonus =
+                    weapon.hit_bonus + skill_level(skills.get(entity), Skill::Ranged);
+                if let Some(attrs) = attributes.get(entity) {
+                    hit_bonus += attrs.quickness.bonus;
+                }
+                let critical = natural_roll == 20;
+                let hit = critical
+                    || (natural_roll != 1
+                        && natural_roll + hit_bonus
+                            >= armor_class(
+                                victim,
+                                &skills,
+                                &attributes,
+                                &defense_bonuses,
+                                &equipped,
+                            ));
+
+                let blocked = if hit {
+                    try_block(victim, &entities, &block_chances, &equipped, &mut rng)
+                } else {
+                    None
+                };
+                if let Some(shield) = blocked {
+                    log.entries.push(format!(
+                        "{} blocks the shot with the {}.",
+                        &target_name.name,
+                        &names.get(shield).unwrap().name
+                    ));
+                } else if hit {
+                    let mut damage = rng.roll_dice(weapon.damage_n_dice, weapon.damage_die_type)
+                        + weapon.damage_bonus;
+                    if critical {
+                        damage += rng.roll_dice(weapon.damage_n_dice, weapon.damage_die_type);
+                    }
+                    let damage = i32::max(1, damage);
+                    if critical {
+                        log.entries.push(format!(
+                            "{} critically shoots {}, for {} hp!",
+                            &name.name, &target_name.name, damage
+                        ));
+                    } else {
+                        log.entries.push(format!(
+                            "{} shoots {}, for {} hp.",
+                            &name.name, &target_name.name, damage
+                        ));
+                    }
+                    effects.add_effect(
+                        Some(entity),
+                        EffectType::Damage {
+                            amount: damage,
+                            damage_type: DamageType::Physical,
+                            weapon: true,
+                        },
+                        Targets::Single { target: victim },
+                    );
+                    if let Some(extra) = inflicts_damage.get(weapon_entity) {
+                        effects.add_effect(
+                            Some(entity),
+                            EffectType::Damage {
+                                amount: extra.damage,
+                                damage_type: extra.damage_type,
+                                weapon: false,
+                            },
+                            Targets::Single { target: victim },
+                        );
+                    }
+                } else {
+                    log.entries.push(format!(
+                        "{} shoots at {}, but misses.",
+                        &name.name, &target_name.name
+                    ));
+                }
+            }
+
+            // Spent ammunition lands where the shot ended, ready to be collected
+            let landing = flight.last().cloned().unwrap_or(start);
+            backpack.remove(ammo);
+            positions
+                .insert(
+                    ammo,
+                    Position {
+                        x: landing.x,
+                        y: landing.y,
+                    },
+                )
+                .expect("Unable to land ammunition.");
+        }
+
+        wants_shoot.clear();
+    }
+}
+
+/// Follows a straight line from `start` towards `target` until something
+/// stops it: a wall, the edge of the map or the first creature in the way.
+/// Returns the tiles crossed and whoever was struck.
+pub fn projectile_path(
+    map: &Map,
+    combat_stats: &ReadStorage<CombatStats>,
+    shooter: Entity,
+    start: Point,
+    target: Point,
+) -> (Vec<Point>, Option<Entity>) {
+    let mut flight: Vec<Point> = Vec::new();
+    for tile in line2d(LineAlg::Bresenham, start, target)
+        .into_iter()
+        .skip(1)
+    {
+        if tile.x < 1 || tile.x > map.width - 1 || tile.y < 1 || tile.y > map.height - 1 {
+            break;
+        }
+        let idx = map.xy_idx(tile.x, tile.y);
+        if map.tiles[idx] == TileType::Wall {
+            break;
+        }
+        flight.push(tile);
+        let victim = map.tile_content[idx]
+            .iter()
+            .find(|e| **e != shooter && combat_stats.get(**e).is_some())
+            .cloned();
+        if victim.is_some() {
+            return (flight, victim);
+        }
+    }
+    (flight, None)
+}
+
+/// The trail clears from the shooter outwards, so the projectile appears to travel.
+pub fn animate_projectile(
+    particle_builder: &mut ParticleBuilder,
+    flight: &[Point],
+    start: Point,
+    target: Point,
+) {
+    let glyph = projectile_glyph(start, target);
+    for (i, tile) in flight.iter().enumerate() {
+        particle_builder.request(
+            tile.x,
+            tile.y,
+            RGB::named(WHEAT),
+            RGB::named(BLACK),
+            glyph,
+            60.0 + 30.0 * i as f32,
+        );
+    }
+}
+
+fn projectile_glyph(from: Point, to: Point) -> FontCharType {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    if dy == 0 || dx.abs() > dy.abs() * 2 {
+        to_cp437('-')
+    } else if dx == 0 || dy.abs() > dx.abs() * 2 {
+        to_cp437('|')
+    } else if (dx > 0) == (dy > 0) {
+        to_cp437('\\')
+    } else {
+        to_cp437('/')
+    }
+}
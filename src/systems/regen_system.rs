@@ -0,0 +1,59 @@
+use super::{
+    gamesystem::regen_interval, Attributes, CombatStats, EffectQueue, EffectType, HungerClock,
+    RunState, Targets,
+};
+use bracket_lib::prelude::RandomNumberGenerator;
+use specs::prelude::*;
+
+/// Wounds slowly close on their own, faster for the fit and experienced.
+/// Nobody heals on an empty stomach.
+pub struct RegenSystem {}
+
+impl<'a> System<'a> for RegenSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, RunState>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        WriteExpect<'a, EffectQueue>,
+        ReadStorage<'a, CombatStats>,
+        ReadStorage<'a, Attributes>,
+        ReadStorage<'a, HungerClock>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player_entity,
+            runstate,
+            mut rng,
+            mut effects,
+            combat_stats,
+            attributes,
+            hunger_clocks,
+        ) = data;
+
+        for (entity, stats) in (&entities, &combat_stats).join() {
+            let my_turn = match *runstate {
+                RunState::PlayerTurn => entity == *player_entity,
+                RunState::MonsterTurn => entity != *player_entity,
+                _ => false,
+            };
+            if !my_turn || stats.hp < 1 || stats.hp >= stats.max_hp {
+                continue;
+            }
+            if hunger_clocks.get(entity).is_some_and(|hc| hc.hungry()) {
+                continue;
+            }
+
+            let fitness = attributes.get(entity).map_or(10, |a| a.fitness.value());
+            if rng.roll_dice(1, regen_interval(fitness, stats.level)) == 1 {
+                effects.add_effect(
+                    None,
+                    EffectType::Healing { amount: 1 },
+                    Targets::Single { target: entity },
+                );
+            }
+        }
+    }
+}
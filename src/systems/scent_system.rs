@@ -0,0 +1,32 @@
+use super::{Map, RunState, SCENT_MAX};
+use bracket_lib::prelude::Point;
+use specs::prelude::*;
+
+const SCENT_DECAY: i32 = 2;
+
+/// Each turn the player's trail fades a little and fresh scent is laid
+/// where they stand.
+pub struct ScentSystem {}
+
+impl<'a> System<'a> for ScentSystem {
+    type SystemData = (
+        WriteExpect<'a, Map>,
+        ReadExpect<'a, Point>,
+        ReadExpect<'a, RunState>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut map, player_pos, runstate) = data;
+
+        if *runstate != RunState::PlayerTurn {
+            return;
+        }
+
+        for scent in map.scent.iter_mut() {
+            *scent = i32::max(0, *scent - SCENT_DECAY);
+        }
+
+        let idx = map.xy_idx(player_pos.x, player_pos.y);
+        map.scent[idx] = SCENT_MAX;
+    }
+}
@@ -0,0 +1,94 @@
+use super::{
+    gamesystem::{mana_regen_turns, skill_level},
+    AreaOfEffect, Attributes, EffectQueue, EffectType, KnownSpell, Mana, Map, RunState, Skill,
+    Skills, Targets, WantsToCastSpell,
+};
+use specs::prelude::*;
+
+/// Pays for and casts the spells that have been chosen, and lets their mana
+/// trickle back between casts.
+pub struct SpellSystem {}
+
+impl<'a> System<'a> for SpellSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, RunState>,
+        ReadExpect<'a, Map>,
+        WriteExpect<'a, EffectQueue>,
+        WriteStorage<'a, WantsToCastSpell>,
+        WriteStorage<'a, Mana>,
+        ReadStorage<'a, KnownSpell>,
+        ReadStorage<'a, AreaOfEffect>,
+        ReadStorage<'a, Attributes>,
+        ReadStorage<'a, Skills>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player_entity,
+            runstate,
+            map,
+            mut effects,
+            mut wants_cast,
+            mut mana,
+            known_spells,
+            aoe,
+            attributes,
+            skills,
+        ) = data;
+
+        for (entity, cast, pool) in (&entities, &wants_cast, &mut mana).join() {
+            let Some(spell) = known_spells.get(cast.spell) else {
+                continue;
+            };
+            if pool.mana < spell.mana_cost {
+                continue;
+            }
+            pool.mana -= spell.mana_cost;
+
+            let targets = match cast.target {
+                None => Targets::Single { target: entity },
+                Some(target) => {
+                    let tile_idx = map.xy_idx(target.x, target.y) as i32;
+                    match aoe.get(cast.spell) {
+                        None => Targets::Tile { tile_idx },
+                        Some(area) => Targets::Area {
+                            center: tile_idx,
+                            radius: area.radius,
+                        },
+                    }
+                }
+            };
+            effects.add_effect(
+                Some(entity),
+                EffectType::ItemUse { item: cast.spell },
+                targets,
+            );
+        }
+        wants_cast.clear();
+
+        // Mana only comes back as turns pass, not every frame
+        for (entity, pool) in (&entities, &mut mana).join() {
+            let my_turn = match *runstate {
+                RunState::PlayerTurn => entity == *player_entity,
+                RunState::MonsterTurn => entity != *player_entity,
+                _ => false,
+            };
+            if !my_turn || pool.mana >= pool.max_mana {
+                continue;
+            }
+            pool.regen_timer += 1;
+            let intelligence = attributes
+                .get(entity)
+                .map_or(0, |attrs| attrs.intelligence.value());
+            if pool.regen_timer
+                >= mana_regen_turns(intelligence, skill_level(skills.get(entity), Skill::Magic))
+            {
+                pool.regen_timer = 0;
+                pool.mana += 1;
+            }
+        }
+    }
+}
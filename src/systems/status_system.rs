@@ -0,0 +1,120 @@
+use super::{
+    gamelog::GameLog, DamageType, EffectQueue, EffectType, Map, Name, Position, RunState,
+    StatusEffects, StatusKind, Targets, Viewshed,
+};
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+/// Ticks every status effect once per turn of whoever it affects, applying
+/// its per-turn behaviour and letting it wear off.
+pub struct StatusSystem {}
+
+impl<'a> System<'a> for StatusSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, RunState>,
+        ReadExpect<'a, Map>,
+        WriteStorage<'a, StatusEffects>,
+        WriteStorage<'a, Viewshed>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Name>,
+        WriteExpect<'a, EffectQueue>,
+        WriteExpect<'a, GameLog>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player_entity,
+            runstate,
+            map,
+            mut statuses,
+            mut viewsheds,
+            positions,
+            names,
+            mut effects,
+            mut log,
+        ) = data;
+
+        let mut cured: Vec<Entity> = Vec::new();
+        for (entity, status) in (&entities, &mut statuses).join() {
+            let proceed = match *runstate {
+                RunState::PlayerTurn => entity == *player_entity,
+                RunState::MonsterTurn => entity != *player_entity,
+                _ => false,
+            };
+            if !proceed {
+                continue;
+            }
+
+            let pos = positions.get(entity);
+            let visible = pos.is_some_and(|pos| map.visible_tiles[map.xy_idx(pos.x, pos.y)]);
+
+            let target = Targets::Single { target: entity };
+            for effect in status.effects.iter_mut() {
+                match effect.kind {
+                    StatusKind::Poisoned => effects.add_effect(
+                        None,
+                        EffectType::Damage {
+                            amount: effect.potency,
+                            damage_type: DamageType::Poison,
+                            weapon: false,
+                        },
+                        target.clone(),
+                    ),
+                    StatusKind::Regenerating => effects.add_effect(
+                        None,
+                        EffectType::Healing {
+                            amount: effect.potency,
+                        },
+                        target.clone(),
+                    ),
+                    StatusKind::Confused => effects.add_effect(
+                        None,
+                        EffectType::Particle {
+                            glyph: to_cp437('?'),
+                            fg: RGB::named(MAGENTA),
+                            bg: RGB::named(BLACK),
+                            lifetime: 200.0,
+                        },
+                        target.clone(),
+                    ),
+                    StatusKind::Blinded => {
+                        if let Some(viewshed) = viewsheds.get_mut(entity) {
+                            viewshed.dirty = true;
+                        }
+                    }
+                    StatusKind::Hasted | StatusKind::Slowed | StatusKind::Paralyzed => {}
+                }
+                effect.turns -= 1;
+            }
+
+            for effect in status.effects.iter().filter(|e| e.turns < 1) {
+                if effect.kind == StatusKind::Blinded {
+                    if let Some(viewshed) = viewsheds.get_mut(entity) {
+                        viewshed.dirty = true;
+                    }
+                }
+                if entity == *player_entity {
+                    log.entries
+                        .push(format!("You are no longer {}.", effect.kind.name()));
+                } else if let (true, Some(name)) = (visible, names.get(entity)) {
+                    log.entries.push(format!(
+                        "The {} is no longer {}.",
+                        name.name,
+                        effect.kind.name()
+                    ));
+                }
+            }
+            status.effects.retain(|e| e.turns > 0);
+            if status.effects.is_empty() {
+                cured.push(entity);
+            }
+        }
+
+        for entity in cured.iter() {
+            statuses.remove(*entity);
+        }
+    }
+}
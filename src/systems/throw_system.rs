@@ -0,0 +1,192 @@
+use super::{
+    gamelog::GameLog,
+    gamesystem::{armor_class, skill_level, try_block},
+    ranged_combat_system::{animate_projectile, projectile_path},
+    Attributes, BlockChance, CombatStats, DamageType, DefenseBonus, EffectQueue, EffectType,
+    Equipped, Identification, InBackpack, Map, MeleeWeapon, Name, NoiseBuilder, ParticleBuilder,
+    Position, Potion, Skill, Skills, Targets, Throwable, WantsToThrow,
+};
+use bracket_lib::prelude::*;
+use specs::prelude::*;
+
+/// Hurls an item from the thrower's backpack along a straight line. Throwing
+/// weapons wound whoever they strike, potions shatter over everyone close by
+/// and anything else simply lands where it stopped.
+pub struct ThrowSystem {}
+
+impl<'a> System<'a> for ThrowSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Map>,
+        WriteExpect<'a, GameLog>,
+        WriteStorage<'a, WantsToThrow>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, CombatStats>,
+        ReadStorage<'a, MeleeWeapon>,
+        ReadStorage<'a, Throwable>,
+        ReadStorage<'a, Potion>,
+        WriteStorage<'a, InBackpack>,
+        WriteStorage<'a, Position>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, Attributes>,
+        ReadStorage<'a, Skills>,
+        ReadStorage<'a, DefenseBonus>,
+        WriteExpect<'a, ParticleBuilder>,
+        WriteExpect<'a, EffectQueue>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        ReadStorage<'a, BlockChance>,
+        ReadExpect<'a, Identification>,
+        WriteExpect<'a, NoiseBuilder>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            map,
+            mut log,
+            mut wants_throw,
+            names,
+            combat_stats,
+            melee_weapons,
+            throwable,
+            potions,
+            mut backpack,
+            mut positions,
+            equipped,
+            attributes,
+            skills,
+            defense_bonuses,
+            mut particle_builder,
+            mut effects,
+            mut rng,
+            block_chances,
+            identification,
+            mut noise_builder,
+        ) = data;
+
+        for (entity, wants_throw, name) in (&entities, &wants_throw, &names).join() {
+            let item = wants_throw.item;
+            if backpack
+                .get(item)
+                .is_none_or(|carried| carried.owner != entity)
+            {
+                continue;
+            }
+            let Some(start) = positions.get(entity).map(|pos| Point::new(pos.x, pos.y)) else {
+                continue;
+            };
+            let item_name = identification.display_name(&names.get(item).unwrap().name);
+            noise_builder.request(start.x, start.y, 8);
+
+            let (flight, victim) =
+                projectile_path(&map, &combat_stats, entity, start, wants_throw.target);
+            animate_projectile(&mut particle_builder, &flight, start, wants_throw.target);
+            let landing = flight.last().cloned().unwrap_or(start);
+            backpack.remove(item);
+
+            // Potions break on impact and splash everyone nearby
+            if potions.get(item).is_some() {
+                log.entries.push(format!("The {} shatters!", item_name));
+                let splash = Targets::Area {
+                    center: map.xy_idx(landing.x, landing.y) as i32,
+                    radius: 1,
+                };
+                effects.add_effect(
+                    Some(entity),
+                    EffectType::Particle {
+                        glyph: to_cp437('░'),
+                        fg: RGB::named(MAGENTA),
+                        bg: RGB::named(BLACK),
+                        lifetime: 200.0,
+                    },
+                    splash.clone(),
+                );
+                effects.add_effect(Some(entity), EffectType::ItemUse { item }, splash);
+                continue;
+            }
+
+            match (victim, throwable.get(item), melee_weapons.get(item)) {
+                (Some(victim), Some(_), Some(weapon)) => {
+                    let target_name = &names.get(victim).unwrap().name;
+                    let natural_roll = rng.roll_dice(1, 20);
+                    let mut hit_bonus =
+                        weapon.hit_bonus + skill_level(skills.get(entity), Skill::Ranged);
+                    if let Some(attrs) = attributes.get(entity) {
+                        hit_bonus += attrs.quickness.bonus;
+                    }
+                    let hit = natural_roll == 20
+                        || (natural_roll != 1
+                            && natural_roll + hit_bonus
+                                >= armor_class(
+                                    victim,
+                                    &skills,
+                                    &attributes,
+                                    &defense_bonuses,
+                                    &equipped,
+                                ));
+                    let blocked = if hit {
+                        try_block(victim, &entities, &block_chances, &equipped, &mut rng)
+                    } else {
+                        None
+                    };
+                    if let Some(shield) = blocked {
+                        log.entries.push(format!(
+                            "{} blocks the throw with the {}.",
+                            &target_name,
+                            &names.get(shield).unwrap().name
+                        ));
+                    } else if hit {
+                        let mut damage = rng
+                            .roll_dice(weapon.damage_n_dice, weapon.damage_die_type)
+                            + weapon.damage_bonus;
+                        if let Some(attrs) = attributes.get(entity) {
+                            damage += attrs.might.bonus;
+                        }
+                        let damage = i32::max(1, damage);
+                        log.entries.push(format!(
+                            "{} throws the {} at {}, for {} hp.",
+                            &name.name, item_name, target_name, damage
+                        ));
+                        effects.add_effect(
+                            Some(entity),
+                            EffectType::Damage {
+                                amount: damage,
+                                damage_type: DamageType::Physical,
+                                weapon: true,
+                            },
+                            Targets::Single { target: victim },
+                        );
+                    } else {
+                        log.entries.push(format!(
+                            "{} throws the {} at {}, but misses.",
+                            &name.name, item_name, target_name
+                        ));
+                    }
+                }
+                (Some(victim), _, _) => {
+                    log.entries.push(format!(
+                        "The {} bounces harmlessly off {}.",
+                        item_name,
+                        &names.get(victim).unwrap().name
+                    ));
+                }
+                (None, _, _) => {
+                    log.entries
+                        .push(format!("{} throws the {}.", &name.name, item_name));
+                }
+            }
+
+            positions
+                .insert(
+                    item,
+                    Position {
+                        x: landing.x,
+                        y: landing.y,
+                    },
+                )
+                .expect("Unable to land thrown item.");
+        }
+
+        wants_throw.clear();
+    }
+}
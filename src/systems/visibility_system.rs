@@ -1,6 +1,9 @@
 use specs::prelude::*;
 
-use super::{GameLog, Hidden, Map, Name, Player, Position, Viewshed};
+use super::{
+    AlertState, Alertness, GameLog, Hidden, Map, Name, Player, Position, RunState, StatusEffects,
+    StatusKind, Viewshed,
+};
 use bracket_lib::prelude::{field_of_view, Point, RandomNumberGenerator};
 
 pub struct VisibilitySystem {}
@@ -16,16 +19,36 @@ impl<'a> System<'a> for VisibilitySystem {
         WriteExpect<'a, RandomNumberGenerator>,
         WriteExpect<'a, GameLog>,
         ReadStorage<'a, Name>,
+        WriteStorage<'a, Alertness>,
+        ReadExpect<'a, Point>,
+        ReadExpect<'a, RunState>,
+        ReadStorage<'a, StatusEffects>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut map, entities, mut viewshed, pos, player, mut hidden, mut rng, mut log, names) =
-            data;
+        let (
+            mut map,
+            entities,
+            mut viewshed,
+            pos,
+            player,
+            mut hidden,
+            mut rng,
+            mut log,
+            names,
+            mut alertness,
+            player_pos,
+            runstate,
+            statuses,
+        ) = data;
         for (ent, viewshed, pos) in (&entities, &mut viewshed, &pos).join() {
             if viewshed.dirty {
                 viewshed.dirty = false;
-                viewshed.visible_tiles =
-                    field_of_view(Point::new(pos.x, pos.y), viewshed.range, &*map);
+                let blinded = statuses
+                    .get(ent)
+                    .is_some_and(|s| s.has(StatusKind::Blinded));
+                let range = if blinded { 1 } else { viewshed.range };
+                viewshed.visible_tiles = field_of_view(Point::new(pos.x, pos.y), range, &*map);
                 viewshed
                     .visible_tiles
                     .retain(|p| p.x >= 0 && p.x < map.width && p.y >= 0 && p.y < map.height);
@@ -42,20 +65,37 @@ impl<'a> System<'a> for VisibilitySystem {
 
                         for e in map.tile_content[idx].iter() {
                             let maybe_hidden = hidden.get(*e);
-                            if let Some(_) = maybe_hidden {
-                                if rng.roll_dice(1, 30) == 1 {
-                                    let name = names.get(*e);
-                                    if let Some(name) = name {
-                                        log.entries
-                                            .push(format!("You've spotted a {}.", &name.name));
-                                    }
-                                    hidden.remove(*e);
+                            if maybe_hidden.is_some() && rng.roll_dice(1, 30) == 1 {
+                                let name = names.get(*e);
+                                if let Some(name) = name {
+                                    log.entries
+                                        .push(format!("You've spotted a {}.", &name.name));
                                 }
+                                hidden.remove(*e);
                             }
                         }
                     }
                 }
             }
         }
+
+        // Unaware monsters that can see the player may notice them
+        if *runstate == RunState::MonsterTurn {
+            let mut noticed: Vec<Entity> = Vec::new();
+            for (ent, viewshed, alert) in (&entities, &viewshed, &alertness).join() {
+                if alert.state == AlertState::Unaware
+                    && viewshed.visible_tiles.contains(&*player_pos)
+                    && rng.roll_dice(1, 4) == 1
+                {
+                    noticed.push(ent);
+                }
+            }
+            for ent in noticed.iter() {
+                if let Some(name) = names.get(*ent) {
+                    log.entries.push(format!("The {} notices you!", &name.name));
+                }
+                alertness.remove(*ent);
+            }
+        }
     }
 }